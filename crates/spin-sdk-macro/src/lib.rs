@@ -23,9 +23,33 @@ const WIT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/wit");
 /// }
 /// ```
 ///
+/// # JSON payloads
+///
+/// With the `json` argument, the payload is deserialized as JSON into the
+/// handler's parameter type, which must implement `serde::DeserializeOwned`.
+/// A payload that cannot be deserialized is logged and reported as a failure
+/// without calling the handler. This requires the `json` feature of the SDK.
+///
+/// ```ignore
+/// # use anyhow::Result;
+/// # use spin_sdk::redis_subscriber;
+/// #[derive(serde::Deserialize)]
+/// struct Order {
+///     id: u64,
+///     quantity: u32,
+/// }
+///
+/// #[redis_subscriber(json)]
+/// async fn on_order(order: Order) -> Result<()> {
+///     println!("order {} for {} items", order.id, order.quantity);
+///     Ok(())
+/// }
+/// ```
+///
 /// See <https://spinframework.dev/redis-trigger> for more information.
 #[proc_macro_attribute]
-pub fn redis_subscriber(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn redis_subscriber(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = syn::parse_macro_input!(attr as RedisSubscriberArgs);
     let func = syn::parse_macro_input!(item as syn::ItemFn);
     let func_name = &func.sig.ident;
 
@@ -38,6 +62,22 @@ pub fn redis_subscriber(_attr: TokenStream, item: TokenStream) -> TokenStream {
         .into();
     }
 
+    let message = if args.json {
+        quote!(match ::spin_sdk::__private::serde_json::from_slice(&msg) {
+            Ok(message) => message,
+            Err(e) => {
+                let e = format!("cannot deserialize Redis payload as JSON: {e}");
+                eprintln!("{}", e);
+                return Err(self::preamble::spin::redis::redis::Error::Other(e));
+            }
+        })
+    } else {
+        quote!(
+            msg.try_into()
+                .expect("cannot convert from Spin Redis payload")
+        )
+    };
+
     quote!(
         #func
         mod __spin_redis {
@@ -54,7 +94,8 @@ pub fn redis_subscriber(_attr: TokenStream, item: TokenStream) -> TokenStream {
             }
             impl self::preamble::exports::spin::redis::inbound_redis::Guest for preamble::Spin {
                 async fn handle_message(msg: self::preamble::exports::spin::redis::inbound_redis::Payload) -> Result<(), self::preamble::spin::redis::redis::Error> {
-                    match super::#func_name(msg.try_into().expect("cannot convert from Spin Redis payload")).await {
+                    let message = #message;
+                    match super::#func_name(message).await {
                         Ok(()) => Ok(()),
                         Err(e) => {
                             eprintln!("{}", e);
@@ -101,6 +142,78 @@ pub fn redis_subscriber(_attr: TokenStream, item: TokenStream) -> TokenStream {
 /// }
 /// ```
 ///
+/// # Decorating responses
+///
+/// The `decorate` argument names a function of type `fn(&mut HeaderMap)`
+/// that is applied to the headers of every response after the handler
+/// returns. This is useful for adding common headers, such as security
+/// headers, in a single place.
+///
+/// ```ignore
+/// use spin_sdk::http::{HeaderMap, HeaderValue, Request, IntoResponse};
+/// use spin_sdk::http_service;
+///
+/// fn security_headers(headers: &mut HeaderMap) {
+///     headers.insert("x-content-type-options", HeaderValue::from_static("nosniff"));
+/// }
+///
+/// #[http_service(decorate = security_headers)]
+/// async fn my_handler(request: Request) -> impl IntoResponse {
+///   // Your logic goes here
+/// }
+/// ```
+///
+/// # JSON request bodies
+///
+/// If the handler's argument is a `Json<T>`, the request body is collected
+/// and deserialized as `T` before the handler is called. A body that is not
+/// valid JSON for `T` is rejected with `400 Bad Request`.
+///
+/// ```ignore
+/// use spin_sdk::http::{IntoResponse, Json, StatusCode};
+/// use spin_sdk::http_service;
+///
+/// #[derive(serde::Deserialize)]
+/// struct CreateUser { name: String }
+///
+/// #[http_service]
+/// async fn create(Json(payload): Json<CreateUser>) -> impl IntoResponse {
+///     (StatusCode::CREATED, format!("created {}", payload.name))
+/// }
+/// ```
+///
+/// # Time limits
+///
+/// The `timeout_ms` argument bounds how long the handler may run. If it has
+/// not returned a response within that many milliseconds, it is cancelled
+/// and the client receives `504 Gateway Timeout` instead. Tasks the handler
+/// spawned are not cancelled.
+///
+/// ```ignore
+/// use spin_sdk::http::{Request, IntoResponse};
+/// use spin_sdk::http_service;
+///
+/// #[http_service(timeout_ms = 5000)]
+/// async fn my_handler(request: Request) -> impl IntoResponse {
+///   // Your logic goes here
+/// }
+/// ```
+///
+/// # `HEAD` requests
+///
+/// Responses to `HEAD` requests are sent without a body, keeping the status
+/// and headers the handler produced, so a handler can serve `HEAD` the same
+/// way as `GET`. See `spin_sdk::http::without_body` for details.
+///
+/// # Panics
+///
+/// WebAssembly components are built with `panic = "abort"`, so a panic in
+/// the handler cannot be caught and turned into a response: it traps the
+/// component instance, and the panic message is written to stderr. The
+/// client receives whatever the host sends for a failed instance, typically
+/// `500 Internal Server Error`. Return an error, such as
+/// `spin_sdk::http::Error`, for failures the handler can anticipate.
+///
 /// # Generated Code
 ///
 /// The macro expands into a module containing a `Spin` struct that implements the
@@ -108,7 +221,8 @@ pub fn redis_subscriber(_attr: TokenStream, item: TokenStream) -> TokenStream {
 /// handler’s entrypoint. This allows the function to be invoked automatically
 /// by the Spin runtime when HTTP requests are received.
 #[proc_macro_attribute]
-pub fn http_service(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn http_service(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = syn::parse_macro_input!(attr as HttpServiceArgs);
     let func = syn::parse_macro_input!(item as syn::ItemFn);
 
     if func.sig.asyncness.is_none() {
@@ -122,6 +236,51 @@ pub fn http_service(_attr: TokenStream, item: TokenStream) -> TokenStream {
 
     let func_name = &func.sig.ident;
 
+    let respond = match &args.decorate {
+        Some(decorate) => {
+            let decorate = path_from_child_module(decorate);
+            quote!(::spin_sdk::http::decorate_response(response, #decorate))
+        }
+        None => quote!(::spin_sdk::http::IntoResponse::into_response(response)),
+    };
+
+    let invoke = |arg: proc_macro2::TokenStream| match args.timeout_ms {
+        Some(timeout_ms) => quote!(
+            match ::spin_sdk::time::timeout(
+                ::std::time::Duration::from_millis(#timeout_ms),
+                super::#func_name(#arg),
+            ).await {
+                Ok(response) => #respond?,
+                Err(_) => {
+                    let response = ::spin_sdk::http::StatusCode::GATEWAY_TIMEOUT;
+                    #respond?
+                }
+            }
+        ),
+        None => quote!({
+            let response = super::#func_name(#arg).await;
+            #respond?
+        }),
+    };
+
+    let call = if takes_json(&func.sig) {
+        let invoke = invoke(quote!(payload));
+        quote!(
+            let response = match ::spin_sdk::http::Json::from_request_body(request).await {
+                Ok(payload) => #invoke,
+                Err(e) => {
+                    let response = Err::<(), _>(e);
+                    #respond?
+                }
+            };
+        )
+    } else {
+        let invoke = invoke(quote!(request));
+        quote!(
+            let response = #invoke;
+        )
+    };
+
     quote!(
         #func
         mod __spin_wasip3_http {
@@ -133,9 +292,168 @@ pub fn http_service(_attr: TokenStream, item: TokenStream) -> TokenStream {
             impl ::spin_sdk::wasip3::exports::http::handler::Guest for self::Spin {
                 async fn handle(request: ::spin_sdk::wasip3::http::types::Request) -> Result<::spin_sdk::wasip3::http::types::Response, ::spin_sdk::wasip3::http::types::ErrorCode> {
                     let request = <::spin_sdk::http::Request as ::spin_sdk::http::FromRequest>::from_request(request)?;
-                    ::spin_sdk::http::IntoResponse::into_response(super::#func_name(request).await)
+                    let is_head = request.method() == ::spin_sdk::http::Method::HEAD;
+                    #call
+                    if is_head {
+                        ::spin_sdk::http::without_body(response)
+                    } else {
+                        Ok(response)
+                    }
+                }
+            }
+        }
+    )
+    .into()
+}
+
+/// Whether the handler's argument is a `Json<T>` extractor, whose body the
+/// generated code must collect and deserialize before calling the handler.
+fn takes_json(sig: &syn::Signature) -> bool {
+    let Some(syn::FnArg::Typed(arg)) = sig.inputs.first() else {
+        return false;
+    };
+    match &*arg.ty {
+        syn::Type::Path(ty) => ty
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Json"),
+        _ => false,
+    }
+}
+
+/// Arguments accepted by the `#[redis_subscriber]` attribute.
+#[derive(Default)]
+struct RedisSubscriberArgs {
+    /// Deserialize the payload as JSON.
+    json: bool,
+}
+
+impl syn::parse::Parse for RedisSubscriberArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut args = Self::default();
+        while !input.is_empty() {
+            let key: syn::Ident = input.parse()?;
+            match key.to_string().as_str() {
+                "json" => args.json = true,
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!("unknown `#[redis_subscriber]` argument `{key}`"),
+                    ));
+                }
+            }
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<syn::Token![,]>()?;
+        }
+        Ok(args)
+    }
+}
+
+/// Arguments accepted by the `#[http_service]` attribute.
+#[derive(Default)]
+struct HttpServiceArgs {
+    /// A function applied to the headers of every response.
+    decorate: Option<syn::Path>,
+    /// The time limit for the handler, in milliseconds.
+    timeout_ms: Option<syn::LitInt>,
+}
+
+impl syn::parse::Parse for HttpServiceArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut args = Self::default();
+        while !input.is_empty() {
+            let key: syn::Ident = input.parse()?;
+            input.parse::<syn::Token![=]>()?;
+            match key.to_string().as_str() {
+                "decorate" => args.decorate = Some(input.parse()?),
+                "timeout_ms" => args.timeout_ms = Some(input.parse()?),
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!("unknown `#[http_service]` argument `{key}`"),
+                    ));
                 }
             }
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<syn::Token![,]>()?;
+        }
+        Ok(args)
+    }
+}
+
+/// Rewrites a path written at the handler's definition site so that it
+/// resolves from inside the module generated by the macro.
+fn path_from_child_module(path: &syn::Path) -> proc_macro2::TokenStream {
+    match path.segments.first() {
+        _ if path.leading_colon.is_some() => quote!(#path),
+        Some(first) if first.ident == "crate" => quote!(#path),
+        Some(first) if first.ident == "self" => {
+            let rest = path.segments.iter().skip(1);
+            quote!(super #(::#rest)*)
+        }
+        _ => quote!(super::#path),
+    }
+}
+
+/// Derives `spin_sdk::pg::FromRow` for a struct with named fields.
+///
+/// Each field is read from the column with the same name, using the field's
+/// `spin_sdk::pg::Decode` implementation. A missing column, or a value that
+/// does not decode as the field's type, is reported as a
+/// `spin_sdk::pg::Error::Decode` naming the column.
+///
+/// ```ignore
+/// use spin_sdk::pg::FromRow;
+///
+/// #[derive(FromRow)]
+/// struct User {
+///     id: i64,
+///     name: String,
+///     email: Option<String>,
+/// }
+///
+/// let users: Vec<User> = db.query("SELECT * FROM users", &[]).await?.into_typed().await?;
+/// ```
+#[proc_macro_derive(FromRow)]
+pub fn derive_from_row(item: TokenStream) -> TokenStream {
+    use syn::ext::IdentExt;
+
+    let input = syn::parse_macro_input!(item as syn::DeriveInput);
+    let fields = match &input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(fields),
+            ..
+        }) => &fields.named,
+        _ => {
+            return syn::Error::new_spanned(
+                &input.ident,
+                "`FromRow` can only be derived for structs with named fields",
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let fields = fields.iter().map(|field| {
+        let ident = field.ident.as_ref().expect("named fields have identifiers");
+        let column = ident.unraw().to_string();
+        quote!(#ident: row.try_get(#column)?)
+    });
+
+    quote!(
+        impl #impl_generics ::spin_sdk::pg::FromRow for #name #ty_generics #where_clause {
+            fn from_row(row: &::spin_sdk::pg::Row) -> ::std::result::Result<Self, ::spin_sdk::pg::Error> {
+                ::std::result::Result::Ok(Self {
+                    #(#fields,)*
+                })
+            }
         }
     )
     .into()
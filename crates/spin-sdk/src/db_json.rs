@@ -0,0 +1,83 @@
+//! Conversion of database values to JSON, shared by the database modules.
+
+use serde_json::Value;
+
+/// Options for converting database values to JSON.
+///
+/// These are taken by `to_json_value_with`, `Row::to_json` and
+/// `QueryResult::collect_json` in each of the database modules.
+#[derive(Clone, Debug, Default)]
+pub struct JsonOptions {
+    /// Render NUMERIC values as JSON numbers rather than strings.
+    ///
+    /// JSON numbers are usually parsed as 64-bit floats, so precision may be
+    /// lost by clients. This is off by default. It applies to PostgreSQL
+    /// NUMERIC values only: MySQL returns DECIMAL values as text that cannot
+    /// be told apart from other strings, and SQLite has no decimal type.
+    pub numeric_as_number: bool,
+}
+
+/// A database value, reduced to the cases that are converted to JSON in
+/// the same way whichever database it came from.
+pub(crate) enum Scalar<'a> {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Uint(u64),
+    Float(f64),
+    Text(&'a str),
+    Numeric(&'a str),
+    Bytes(&'a [u8]),
+}
+
+impl JsonOptions {
+    /// Converts `value` to JSON.
+    ///
+    /// Non-finite floats, which JSON cannot represent, become `null`, and
+    /// bytes become hex strings prefixed with `0x`.
+    pub(crate) fn convert(&self, value: Scalar) -> Value {
+        match value {
+            Scalar::Null => Value::Null,
+            Scalar::Bool(b) => Value::Bool(b),
+            Scalar::Int(n) => n.into(),
+            Scalar::Uint(n) => n.into(),
+            Scalar::Float(n) => n.into(),
+            Scalar::Text(s) => Value::String(s.to_owned()),
+            Scalar::Numeric(s) => match s.parse::<serde_json::Number>() {
+                Ok(n) if self.numeric_as_number => Value::Number(n),
+                _ => Value::String(s.to_owned()),
+            },
+            Scalar::Bytes(b) => {
+                use std::fmt::Write;
+
+                let mut s = String::with_capacity(2 + b.len() * 2);
+                s.push_str("0x");
+                for byte in b {
+                    _ = write!(s, "{byte:02x}");
+                }
+                Value::String(s)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn scalars() {
+        let options = JsonOptions::default();
+        assert_eq!(json!(null), options.convert(Scalar::Float(f64::NAN)));
+        assert_eq!(json!(null), options.convert(Scalar::Float(f64::INFINITY)));
+        assert_eq!(json!("0x00ff"), options.convert(Scalar::Bytes(&[0, 255])));
+        assert_eq!(json!("1.10"), options.convert(Scalar::Numeric("1.10")));
+
+        let numbers = JsonOptions {
+            numeric_as_number: true,
+        };
+        assert_eq!(json!(1.1), numbers.convert(Scalar::Numeric("1.10")));
+        assert_eq!(json!("NaN"), numbers.convert(Scalar::Numeric("NaN")));
+    }
+}
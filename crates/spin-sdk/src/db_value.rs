@@ -0,0 +1,46 @@
+//! Conversions shared by the PostgreSQL and MySQL modules, whose
+//! `ParameterValue` and `DbValue` types mirror each other.
+
+/// Implements `From<ParameterValue> for DbValue` in the calling module.
+///
+/// Each listed variant must carry the same data in both types. `DbNull` is
+/// always included.
+macro_rules! db_value_from_parameter {
+    ($($variant:ident),* $(,)?) => {
+        /// Converts a parameter to the value the database would return for it.
+        ///
+        /// Each [`ParameterValue`] variant has a matching [`DbValue`] variant, so
+        /// this is useful for building rows in tests, or for checking that a
+        /// value survives a round trip through [`Into<ParameterValue>`] and
+        /// [`Decode`].
+        impl From<ParameterValue> for DbValue {
+            fn from(value: ParameterValue) -> DbValue {
+                match value {
+                    $(ParameterValue::$variant(v) => DbValue::$variant(v),)*
+                    ParameterValue::DbNull => DbValue::DbNull,
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use db_value_from_parameter;
+
+/// Defines a `round_trip` test helper in the calling module, which encodes a
+/// value as a `ParameterValue`, converts it to the corresponding `DbValue`
+/// and decodes it again with `Decode`.
+#[cfg(test)]
+macro_rules! round_trip_helper {
+    () => {
+        fn round_trip<T>(value: T)
+        where
+            T: Into<ParameterValue> + Decode + Clone + PartialEq + std::fmt::Debug,
+        {
+            let db_value = DbValue::from(value.clone().into());
+            assert_eq!(value, T::decode(&db_value).unwrap(), "via {db_value:?}");
+        }
+    };
+}
+
+#[cfg(test)]
+pub(crate) use round_trip_helper;
@@ -6,17 +6,41 @@ use std::any::Any;
 use wasip3::{
     http::types,
     http_compat::{
-        http_from_wasi_request, http_from_wasi_response, http_into_wasi_request,
-        http_into_wasi_response,
+        RequestOptionsExtension, http_from_wasi_request, http_from_wasi_response,
+        http_into_wasi_request, http_into_wasi_response,
     },
 };
 
+pub mod access_log;
 pub mod body;
+#[cfg(feature = "compression")]
+#[cfg_attr(docsrs, doc(cfg(feature = "compression")))]
+pub mod compression;
+#[cfg(feature = "csv")]
+#[cfg_attr(docsrs, doc(cfg(feature = "csv")))]
+pub mod csv;
 /// gRPC helpers for serving tonic services.
 #[cfg(feature = "grpc")]
 #[cfg_attr(docsrs, doc(cfg(feature = "grpc")))]
 pub mod grpc;
 
+#[cfg(feature = "key-value")]
+#[cfg_attr(docsrs, doc(cfg(feature = "key-value")))]
+pub mod idempotency;
+pub mod responses;
+
+mod error_code;
+mod headers;
+mod request;
+#[cfg(feature = "json")]
+mod response;
+
+pub use error_code::ErrorCodeExt;
+pub use headers::{HeaderExt, InvalidMediaType, MediaType};
+pub use request::{RequestBuilderExt, RequestExt, RequireContentType};
+#[cfg(feature = "json")]
+pub use response::ResponseExt;
+
 /// A alias for [`std::result::Result`] that uses [`Error`] as the default error type.
 ///
 /// This allows functions throughout the crate to return `Result<T>`
@@ -76,7 +100,18 @@ impl std::fmt::Display for Error {
     }
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    // `Display` shows the wrapped error's own message, so expose that error's
+    // source here; this lets logging walk the full causal chain, even though
+    // the error reported to the host is only a message.
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::ErrorCode(_) | Error::Response(_) => None,
+            Error::HttpError(e) => e.source(),
+            Error::Other(e) => e.source(),
+        }
+    }
+}
 
 impl From<http::Error> for Error {
     fn from(err: http::Error) -> Error {
@@ -90,7 +125,10 @@ impl From<anyhow::Error> for Error {
             Ok(code) => Error::ErrorCode(code),
             Err(other) => match other.downcast::<Error>() {
                 Ok(err) => err,
-                Err(other) => Error::Other(other.into_boxed_dyn_error()),
+                Err(other) => match attached_status(&other) {
+                    Some(status) => Error::with_status(status, other),
+                    None => Error::Other(other.into_boxed_dyn_error()),
+                },
             },
         }
     }
@@ -134,6 +172,222 @@ impl Error {
     pub fn other(msg: impl Into<String>) -> Self {
         anyhow::Error::msg(msg.into()).into()
     }
+
+    /// Creates an error that is returned to the client with the given status.
+    ///
+    /// When a handler returns this error, the response has the given status
+    /// code, rather than the error becoming an internal server error. For a
+    /// client error (4xx), the error message is the body of the response.
+    /// For other statuses the body is only the status's reason phrase, such
+    /// as `Service Unavailable`; the message of a server error (5xx) is
+    /// written to stderr, where it appears in the component's logs, but is
+    /// not sent to the client.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use spin_sdk::http::{Error, IntoResponse, Request, StatusCode};
+    /// use spin_sdk::http_service;
+    ///
+    /// #[http_service]
+    /// async fn handler(req: Request) -> Result<impl IntoResponse, Error> {
+    ///     if req.uri().path() != "/" {
+    ///         return Err(Error::with_status(StatusCode::NOT_FOUND, "no such page"));
+    ///     }
+    ///     Ok("Hello, world!")
+    /// }
+    /// ```
+    pub fn with_status(
+        status: StatusCode,
+        err: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        Error::Other(Box::new(StatusError {
+            status,
+            source: err.into(),
+        }))
+    }
+
+    /// Creates an error from an application error type that chooses its own
+    /// status code through [`AsStatusCode`].
+    pub fn from_status_error<E>(err: E) -> Self
+    where
+        E: AsStatusCode + std::error::Error + Send + Sync + 'static,
+    {
+        Self::with_status(err.status_code(), err)
+    }
+}
+
+/// An error type that knows which HTTP status code it should produce.
+///
+/// Implement this for application error types, then convert them with
+/// [`Error::from_status_error`] (typically in a `From` impl) so that handlers
+/// returning them respond with a status such as `404 Not Found` or
+/// `409 Conflict` instead of `500 Internal Server Error`.
+///
+/// # Examples
+///
+/// ```ignore
+/// use spin_sdk::http::{AsStatusCode, Error, StatusCode};
+///
+/// #[derive(Debug, thiserror::Error)]
+/// enum AppError {
+///     #[error("user not found")]
+///     NotFound,
+///     #[error("user already exists")]
+///     Conflict,
+/// }
+///
+/// impl AsStatusCode for AppError {
+///     fn status_code(&self) -> StatusCode {
+///         match self {
+///             AppError::NotFound => StatusCode::NOT_FOUND,
+///             AppError::Conflict => StatusCode::CONFLICT,
+///         }
+///     }
+/// }
+///
+/// impl From<AppError> for Error {
+///     fn from(err: AppError) -> Self {
+///         Error::from_status_error(err)
+///     }
+/// }
+/// ```
+pub trait AsStatusCode {
+    /// The status code to respond with for this error.
+    fn status_code(&self) -> StatusCode;
+}
+
+/// Extension methods for mapping the error of a [`Result`](std::result::Result)
+/// to an HTTP status.
+pub trait ResultExt<T> {
+    /// Maps the error to an [`Error`] that responds with `status`.
+    ///
+    /// See [`Error::with_status`].
+    fn map_err_to_status(self, status: StatusCode) -> Result<T, Error>;
+}
+
+impl<T, E> ResultExt<T> for std::result::Result<T, E>
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    fn map_err_to_status(self, status: StatusCode) -> Result<T, Error> {
+        self.map_err(|e| Error::with_status(status, e))
+    }
+}
+
+/// Extension methods for attaching an HTTP status to an [`anyhow::Error`].
+///
+/// Handlers returning `anyhow::Result` normally answer any error with
+/// `500 Internal Server Error`. An error marked with
+/// [`with_status`](AnyhowExt::with_status) instead produces a response with
+/// that status, even if more context is added to it afterwards. As with
+/// [`Error::with_status`], only client errors (4xx) use the error message as
+/// the body of the response. For server errors (5xx), the full error chain is
+/// also written to stderr, where it appears in the component's logs.
+///
+/// # Examples
+///
+/// ```ignore
+/// use anyhow::Context;
+/// use spin_sdk::http::{AnyhowExt, IntoResponse, Request, StatusCode};
+/// use spin_sdk::http_service;
+///
+/// #[http_service]
+/// async fn handler(req: Request) -> anyhow::Result<impl IntoResponse> {
+///     let id: u32 = req
+///         .uri()
+///         .path()
+///         .trim_start_matches('/')
+///         .parse()
+///         .context("invalid user id")
+///         .with_status(StatusCode::BAD_REQUEST)?;
+///     Ok(format!("user {id}"))
+/// }
+/// ```
+pub trait AnyhowExt {
+    /// The type returned once the status is attached.
+    type Output;
+
+    /// Attaches `status` as the status to respond with if this error is
+    /// returned from a handler.
+    fn with_status(self, status: StatusCode) -> Self::Output;
+}
+
+impl AnyhowExt for anyhow::Error {
+    type Output = anyhow::Error;
+
+    fn with_status(self, status: StatusCode) -> anyhow::Error {
+        anyhow::Error::new(StatusError {
+            status,
+            source: self.into(),
+        })
+    }
+}
+
+impl<T> AnyhowExt for anyhow::Result<T> {
+    type Output = anyhow::Result<T>;
+
+    fn with_status(self, status: StatusCode) -> anyhow::Result<T> {
+        self.map_err(|e| e.with_status(status))
+    }
+}
+
+/// The status attached to `err` or to any error in its chain by
+/// [`AnyhowExt::with_status`], if any.
+fn attached_status(err: &anyhow::Error) -> Option<StatusCode> {
+    err.chain()
+        .find_map(|e| e.downcast_ref::<StatusError>())
+        .map(|e| e.status)
+}
+
+/// The error carried in [`Error::Other`] by [`Error::with_status`].
+#[derive(Debug)]
+struct StatusError {
+    status: StatusCode,
+    source: Box<dyn std::error::Error + Send + Sync>,
+}
+
+impl StatusError {
+    /// The body of the response to this error.
+    ///
+    /// Only client errors (4xx) repeat the error message; other statuses
+    /// respond with the canonical reason phrase, so that the details of a
+    /// server failure are not disclosed to the client.
+    fn response_body(&self) -> String {
+        if self.status.is_client_error() {
+            self.source.to_string()
+        } else {
+            self.status
+                .canonical_reason()
+                .unwrap_or_default()
+                .to_owned()
+        }
+    }
+
+    /// The error message followed by the messages of its sources, separated
+    /// by `: `.
+    fn chain(&self) -> String {
+        let mut chain = self.source.to_string();
+        let mut source = self.source.source();
+        while let Some(err) = source {
+            chain.push_str(": ");
+            chain.push_str(&err.to_string());
+            source = err.source();
+        }
+        chain
+    }
+}
+
+impl std::fmt::Display for StatusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.source.fmt(f)
+    }
+}
+
+impl std::error::Error for StatusError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source.source()
+    }
 }
 
 impl<Ok: IntoResponse, Err: Into<Error>> IntoResponse for Result<Ok, Err> {
@@ -150,28 +404,343 @@ impl<Ok: IntoResponse, Err: Into<Error>> IntoResponse for Result<Ok, Err> {
                     err if err.is::<http::uri::InvalidUri>() => {
                         Err(types::ErrorCode::HttpRequestUriInvalid)
                     }
-                    err => Err(types::ErrorCode::InternalError(Some(err.to_string()))),
+                    err => Err(types::ErrorCode::internal(err)),
+                },
+                Error::Other(other) => match other.downcast::<StatusError>() {
+                    Ok(err) => {
+                        if err.status.is_server_error() {
+                            eprintln!("{}", err.chain());
+                        }
+                        (err.status, err.response_body()).into_response()
+                    }
+                    Err(other) => Err(types::ErrorCode::internal(other)),
                 },
-                Error::Other(other) => {
-                    Err(types::ErrorCode::InternalError(Some(other.to_string())))
-                }
             },
         }
     }
 }
 
+/// Converts `response` into a [`wasip3::http::types::Response`], then lets
+/// `decorate` modify its headers before it is returned.
+///
+/// This is the function behind the `decorate` argument of the
+/// [`http_service`](crate::http_service) macro, which allows a component to
+/// add common headers (such as `Server` or security headers) to every
+/// response in one place rather than in each handler branch.
+///
+/// # Examples
+///
+/// ```ignore
+/// use spin_sdk::http::{HeaderMap, HeaderValue, IntoResponse, Request};
+/// use spin_sdk::http_service;
+///
+/// fn security_headers(headers: &mut HeaderMap) {
+///     headers.insert("x-content-type-options", HeaderValue::from_static("nosniff"));
+/// }
+///
+/// #[http_service(decorate = security_headers)]
+/// async fn handler(_req: Request) -> impl IntoResponse {
+///     "Hello, world!"
+/// }
+/// ```
+pub fn decorate_response(
+    response: impl IntoResponse,
+    decorate: impl FnOnce(&mut HeaderMap),
+) -> HttpResult<types::Response> {
+    let response = Response::from_response(response.into_response()?)?;
+    let mut response = response.map(restream);
+    decorate(response.headers_mut());
+    response.into_response()
+}
+
+/// Converts `response` into a [`wasip3::http::types::Response`] with the
+/// same status and headers but no body.
+///
+/// A response to a `HEAD` request must not have a body, but should otherwise
+/// match the response to the equivalent `GET`, including its
+/// `Content-Length`. The [`http_service`](crate::http_service) macro applies
+/// this to responses to `HEAD` requests, so handlers can treat `HEAD` like
+/// `GET`; handlers that want to avoid building the body at all can check
+/// for [`Method::HEAD`] themselves.
+pub fn without_body(response: impl IntoResponse) -> HttpResult<types::Response> {
+    let response = Response::from_response(response.into_response()?)?;
+    let (parts, _body) = response.into_parts();
+    http::Response::from_parts(parts, EmptyBody::new()).into_response()
+}
+
+/// Makes the `Content-Length` header of `response` agree with its body.
+///
+/// If the size of the body is known exactly, as it is for fully-buffered
+/// bodies such as [`FullBody`] or a `String`, `Content-Length` is set to that
+/// size, replacing any value the handler set. Otherwise the body is streamed,
+/// so any `Content-Length` is removed and the host frames the body itself
+/// (for example with chunked encoding). `Transfer-Encoding` is always removed,
+/// as framing is the host's responsibility.
+///
+/// Call this on a response before returning it to avoid framing errors from
+/// a `Content-Length` that does not match the body.
+///
+/// # Examples
+///
+/// ```ignore
+/// use spin_sdk::http::{IntoResponse, Request, normalize_content_length};
+/// use spin_sdk::http_service;
+///
+/// #[http_service]
+/// async fn handler(_req: Request) -> impl IntoResponse {
+///     let mut response = http::Response::builder()
+///         .header("content-length", "100") // wrong!
+///         .body(String::from("Hello, world!"))
+///         .unwrap();
+///     normalize_content_length(&mut response); // now 13
+///     response
+/// }
+/// ```
+pub fn normalize_content_length<B: http_body::Body>(response: &mut http::Response<B>) {
+    let size = response.body().size_hint().exact();
+    let headers = response.headers_mut();
+    headers.remove(http::header::TRANSFER_ENCODING);
+    match size {
+        Some(size) => {
+            headers.insert(http::header::CONTENT_LENGTH, HeaderValue::from(size));
+        }
+        None => {
+            headers.remove(http::header::CONTENT_LENGTH);
+        }
+    }
+}
+
+/// Default options for outbound requests.
+///
+/// Set these once with [`set_default_request_options`] to apply, for example,
+/// a connect timeout to every outbound request instead of attaching a
+/// [`RequestOptionsExtension`](wasip3::http_compat::RequestOptionsExtension)
+/// to each one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DefaultRequestOptions {
+    /// The timeout for establishing a connection.
+    pub connect_timeout: Option<std::time::Duration>,
+    /// The timeout for receiving the first byte of the response.
+    pub first_byte_timeout: Option<std::time::Duration>,
+    /// The timeout between successive bytes of the response.
+    pub between_bytes_timeout: Option<std::time::Duration>,
+}
+
+static DEFAULT_REQUEST_OPTIONS: std::sync::Mutex<DefaultRequestOptions> =
+    std::sync::Mutex::new(DefaultRequestOptions {
+        connect_timeout: None,
+        first_byte_timeout: None,
+        between_bytes_timeout: None,
+    });
+
+/// Sets the options applied to outbound requests converted with
+/// [`IntoRequest`], including those sent with [`send`].
+///
+/// Options set on an individual request through a
+/// [`RequestOptionsExtension`](wasip3::http_compat::RequestOptionsExtension)
+/// take precedence; the defaults only fill in the timeouts that the request
+/// leaves unset. Defaults that the host does not support are ignored.
+///
+/// # Examples
+///
+/// ```ignore
+/// use spin_sdk::http::{DefaultRequestOptions, set_default_request_options};
+/// use std::time::Duration;
+///
+/// set_default_request_options(DefaultRequestOptions {
+///     connect_timeout: Some(Duration::from_secs(2)),
+///     ..Default::default()
+/// });
+/// ```
+pub fn set_default_request_options(options: DefaultRequestOptions) {
+    *DEFAULT_REQUEST_OPTIONS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner()) = options;
+}
+
+/// The options set by [`set_default_request_options`].
+pub fn default_request_options() -> DefaultRequestOptions {
+    *DEFAULT_REQUEST_OPTIONS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+/// Merges the default request options into the request's own options.
+fn apply_default_request_options(extensions: &mut http::Extensions) {
+    let defaults = default_request_options();
+    if defaults == DefaultRequestOptions::default() {
+        return;
+    }
+    // Clone any per-request options, as the original may be immutable.
+    let options = match extensions.get::<RequestOptionsExtension>() {
+        Some(options) => options.0.clone(),
+        None => types::RequestOptions::new(),
+    };
+    let nanos = |d: std::time::Duration| d.as_nanos().try_into().unwrap_or(u64::MAX);
+    if let Some(timeout) = defaults.connect_timeout
+        && options.get_connect_timeout().is_none()
+    {
+        _ = options.set_connect_timeout(Some(nanos(timeout)));
+    }
+    if let Some(timeout) = defaults.first_byte_timeout
+        && options.get_first_byte_timeout().is_none()
+    {
+        _ = options.set_first_byte_timeout(Some(nanos(timeout)));
+    }
+    if let Some(timeout) = defaults.between_bytes_timeout
+        && options.get_between_bytes_timeout().is_none()
+    {
+        _ = options.set_between_bytes_timeout(Some(nanos(timeout)));
+    }
+    extensions.insert(RequestOptionsExtension(options));
+}
+
 /// Sends an HTTP request and returns the corresponding [`wasip3::http::types::Response`].
 ///
 /// This function converts the provided value into a [`wasip3::http::types::Request`] using the
 /// [`IntoRequest`] trait, dispatches it to the WASI HTTP handler, and awaits
 /// the resulting response. It provides a convenient high-level interface for
 /// issuing HTTP requests within a WASI environment.
+///
+/// A response is returned whatever its status, so a `404 Not Found` or
+/// `500 Internal Server Error` from the server is `Ok` and the caller must
+/// check the status. Use [`send_expecting_success`] to treat those as errors.
 pub async fn send(request: impl IntoRequest) -> HttpResult<Response> {
     let request = request.into_request()?;
     let response = wasip3::http::client::send(request).await?;
     Response::from_response(response)
 }
 
+/// Sends an HTTP request like [`send`], but fails unless the response has a
+/// success (2xx) status.
+///
+/// Any other response is returned as [`Error::Response`], body included, so
+/// it can be inspected by the caller, or propagated with `?` from a handler
+/// to pass the upstream response on to the client unchanged.
+///
+/// # Examples
+///
+/// ```ignore
+/// use spin_sdk::http::{EmptyBody, send_expecting_success};
+///
+/// let request = http::Request::get("https://example.com/api/items").body(EmptyBody::new())?;
+/// let response = send_expecting_success(request).await?;
+/// ```
+pub async fn send_expecting_success(request: impl IntoRequest) -> Result<Response> {
+    let request = request.into_request()?;
+    let response = wasip3::http::client::send(request).await?;
+    if !(200..300).contains(&response.get_status_code()) {
+        return Err(Error::Response(response));
+    }
+    Ok(Response::from_response(response)?)
+}
+
+/// Forwards `request` to the upstream server at `upstream_base`, returning
+/// the upstream response.
+///
+/// The scheme and authority of the request are replaced with those of
+/// `upstream_base`, and its path is appended to the path of `upstream_base`,
+/// so a request for `/users?page=2` proxied to `https://api.example.com/v1`
+/// is sent to `https://api.example.com/v1/users?page=2`. The method, the
+/// remaining headers and the body are forwarded as they are; the body is
+/// streamed to the upstream rather than buffered, and the response body is
+/// likewise streamed back.
+///
+/// Hop-by-hop headers such as `Connection` and `Transfer-Encoding`, which
+/// describe a single connection and must not be forwarded, are removed from
+/// both the request and the response, as is the `Host` header of the
+/// request.
+///
+/// # Examples
+///
+/// ```ignore
+/// use spin_sdk::http::{IntoResponse, Request, Uri, proxy_to};
+/// use spin_sdk::http_service;
+///
+/// #[http_service]
+/// async fn handler(req: Request) -> impl IntoResponse {
+///     let upstream = Uri::from_static("https://api.example.com/v1");
+///     proxy_to(req, &upstream).await
+/// }
+/// ```
+pub async fn proxy_to(
+    request: Request,
+    upstream_base: &Uri,
+) -> HttpResult<Response<impl http_body::Body<Data = bytes::Bytes, Error = types::ErrorCode> + use<>>>
+{
+    let (mut parts, body) = request.into_parts();
+    parts.uri = upstream_uri(upstream_base, &parts.uri)?;
+    remove_hop_by_hop_headers(&mut parts.headers);
+    parts.headers.remove(http::header::HOST);
+
+    let response = send(http::Request::from_parts(parts, restream(body))).await?;
+    let mut response = response.map(restream);
+    remove_hop_by_hop_headers(response.headers_mut());
+    Ok(response)
+}
+
+/// Wraps an incoming `body` so that a message built from it is always
+/// written out anew when it is converted back to its WASI form.
+///
+/// The conversions in `wasip3::http_compat` try to recognise a message whose
+/// body has not been read yet and hand back the original WASI message, which
+/// would drop any changes made to its headers. In wasip3 0.7.0 that check
+/// downcasts the whole `Request<T>` or `Response<T>` rather than its body, so
+/// it never matches and this wrapper has no effect; it keeps header changes
+/// in place should the check be corrected to look at the body.
+fn restream<B: http_body::Body>(
+    body: B,
+) -> impl http_body::Body<Data = B::Data, Error = B::Error> + use<B> {
+    use http_body_util::BodyExt;
+    body.map_err(std::convert::identity)
+}
+
+/// Joins the path and query of `uri` onto `base`.
+fn upstream_uri(base: &Uri, uri: &Uri) -> HttpResult<Uri> {
+    if base.scheme().is_none() || base.authority().is_none() {
+        return Err(types::ErrorCode::HttpRequestUriInvalid);
+    }
+    let base_path = base.path().trim_end_matches('/');
+    let path_and_query = uri.path_and_query().map_or("/", |pq| pq.as_str());
+    let mut parts = base.clone().into_parts();
+    parts.path_and_query = Some(
+        format!("{base_path}{path_and_query}")
+            .parse()
+            .map_err(|_| types::ErrorCode::HttpRequestUriInvalid)?,
+    );
+    Uri::from_parts(parts).map_err(|_| types::ErrorCode::HttpRequestUriInvalid)
+}
+
+/// Removes headers that apply only to a single connection, including any
+/// named in the `Connection` header.
+fn remove_hop_by_hop_headers(headers: &mut HeaderMap) {
+    const HOP_BY_HOP: &[&str] = &[
+        "connection",
+        "keep-alive",
+        "proxy-authenticate",
+        "proxy-authorization",
+        "proxy-connection",
+        "te",
+        "trailer",
+        "transfer-encoding",
+        "upgrade",
+    ];
+
+    let listed: Vec<HeaderName> = headers
+        .get_all(http::header::CONNECTION)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|name| name.trim().parse().ok())
+        .collect();
+    for name in listed {
+        headers.remove(name);
+    }
+    for name in HOP_BY_HOP {
+        headers.remove(*name);
+    }
+}
+
 #[cfg(feature = "http-middleware")]
 mod middleware {
     use crate::wit_bindgen;
@@ -422,7 +991,8 @@ where
     T::Data: Into<Vec<u8>>,
     T::Error: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
 {
-    fn into_request(self) -> HttpResult<types::Request> {
+    fn into_request(mut self) -> HttpResult<types::Request> {
+        apply_default_request_options(self.extensions_mut());
         http_into_wasi_request(self)
     }
 }
@@ -486,10 +1056,29 @@ where
     }
 }
 
+/// Responds with the status and an empty body.
+///
+/// `Content-Length: 0` is set, except for the statuses that must not carry
+/// it: informational (1xx) responses, `204 No Content` and
+/// `304 Not Modified`.
 impl IntoResponse for http::StatusCode {
     fn into_response(self) -> HttpResult<types::Response> {
-        (self, EmptyBody::new()).into_response()
+        empty_response(self).into_response()
+    }
+}
+
+fn empty_response(status: http::StatusCode) -> http::Response<EmptyBody> {
+    let mut response = http::Response::new(EmptyBody::new());
+    *response.status_mut() = status;
+    if !status.is_informational()
+        && status != http::StatusCode::NO_CONTENT
+        && status != http::StatusCode::NOT_MODIFIED
+    {
+        response
+            .headers_mut()
+            .insert(http::header::CONTENT_LENGTH, HeaderValue::from(0));
     }
+    response
 }
 
 impl IntoResponse for &'static str {
@@ -553,6 +1142,46 @@ where
     }
 }
 
+/// Responds with the given status, headers and body.
+///
+/// Headers are appended in order, so a name may be repeated to send several
+/// values. An invalid header name or value results in an
+/// [`ErrorCode::InternalError`](types::ErrorCode::InternalError).
+///
+/// # Examples
+///
+/// ```ignore
+/// use spin_sdk::http::{IntoResponse, Request, StatusCode};
+/// use spin_sdk::http_service;
+///
+/// #[http_service]
+/// async fn handler(_req: Request) -> impl IntoResponse {
+///     (
+///         StatusCode::CREATED,
+///         vec![("content-type", "text/plain"), ("location", "/items/1")],
+///         String::from("created"),
+///     )
+/// }
+/// ```
+impl<T> IntoResponse for (http::StatusCode, Vec<(&str, &str)>, T)
+where
+    T: http_body::Body + Any,
+    T::Data: Into<Vec<u8>>,
+    T::Error: Into<Box<dyn std::error::Error + Send + Sync + 'static>>,
+{
+    fn into_response(self) -> HttpResult<types::Response> {
+        let (status, headers, body) = self;
+        let mut builder = http::Response::builder().status(status);
+        for (name, value) in headers {
+            builder = builder.header(name, value);
+        }
+        builder
+            .body(body)
+            .map_err(types::ErrorCode::internal)?
+            .into_response()
+    }
+}
+
 /// A JSON wrapper for request and response bodies.
 ///
 /// Wraps a value of type `T` and serializes it as JSON when used as a response,
@@ -577,36 +1206,257 @@ where
 #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
 pub struct Json<T>(pub T);
 
+#[cfg(feature = "json")]
+impl<T: serde::de::DeserializeOwned> Json<T> {
+    /// Reads the whole body of `request` and deserializes it as JSON.
+    ///
+    /// A body that is not valid JSON for `T` results in an error that
+    /// responds with `400 Bad Request`. This is the extraction that
+    /// [`http_service`](crate::http_service) performs for handlers that take a
+    /// `Json<T>` argument:
+    ///
+    /// ```ignore
+    /// use spin_sdk::http::{IntoResponse, Json, StatusCode};
+    /// use spin_sdk::http_service;
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// struct CreateUser { name: String }
+    ///
+    /// #[http_service]
+    /// async fn create(Json(payload): Json<CreateUser>) -> impl IntoResponse {
+    ///     (StatusCode::CREATED, format!("created {}", payload.name))
+    /// }
+    /// ```
+    pub async fn from_request_body(request: Request) -> Result<Self, Error> {
+        use body::IncomingBodyExt;
+
+        let body = request.into_body().bytes().await?;
+        serde_json::from_slice(&body)
+            .map(Json)
+            .map_err(|e| Error::with_status(StatusCode::BAD_REQUEST, e))
+    }
+}
+
 #[cfg(feature = "json")]
 impl<T: serde::Serialize> IntoResponse for Json<T> {
     fn into_response(self) -> HttpResult<types::Response> {
-        let body = serde_json::to_vec(&self.0)
-            .map_err(|e| types::ErrorCode::InternalError(Some(e.to_string())))?;
-        let mut resp = http::Response::builder()
-            .status(http::StatusCode::OK)
-            .body(FullBody::new(bytes::Bytes::from(body)))
-            .unwrap();
-        resp.headers_mut().insert(
-            http::header::CONTENT_TYPE,
-            http::HeaderValue::from_static("application/json"),
-        );
-        resp.into_response()
+        json_response(http::StatusCode::OK, &self.0)?.into_response()
     }
 }
 
 #[cfg(feature = "json")]
 impl<T: serde::Serialize> IntoResponse for (http::StatusCode, Json<T>) {
     fn into_response(self) -> HttpResult<types::Response> {
-        let body = serde_json::to_vec(&self.1.0)
-            .map_err(|e| types::ErrorCode::InternalError(Some(e.to_string())))?;
-        let mut resp = http::Response::builder()
-            .status(self.0)
-            .body(FullBody::new(bytes::Bytes::from(body)))
-            .unwrap();
+        json_response(self.0, &self.1.0)?.into_response()
+    }
+}
+
+/// Serializes `value` into a JSON response with the given status.
+#[cfg(feature = "json")]
+fn json_response<T: serde::Serialize>(
+    status: http::StatusCode,
+    value: &T,
+) -> HttpResult<http::Response<FullBody<bytes::Bytes>>> {
+    let body = serde_json::to_vec(value).map_err(types::ErrorCode::internal)?;
+    let mut resp = http::Response::builder()
+        .status(status)
+        .body(FullBody::new(bytes::Bytes::from(body)))
+        .unwrap();
+    resp.headers_mut().insert(
+        http::header::CONTENT_TYPE,
+        http::HeaderValue::from_static("application/json"),
+    );
+    Ok(resp)
+}
+
+/// A newline-delimited JSON (NDJSON) response streamed from a
+/// [`Stream`](futures::Stream) of values.
+///
+/// Each value is serialized as one line of JSON and sent as soon as the
+/// stream yields it, with the `Content-Type: application/x-ndjson` header.
+/// This suits large exports and other results that should not be collected
+/// into memory before responding. See [`body::ndjson`] for details.
+///
+/// # Examples
+///
+/// ```ignore
+/// use spin_sdk::http::{IntoResponse, Ndjson, Request};
+/// use spin_sdk::http_service;
+///
+/// #[derive(serde::Serialize)]
+/// struct Record { id: u32 }
+///
+/// #[http_service]
+/// async fn handler(_req: Request) -> impl IntoResponse {
+///     let (tx, records) = futures::channel::mpsc::channel(16);
+///     spin_sdk::wasip3::spawn(produce_records(tx));
+///     Ndjson(records)
+/// }
+/// ```
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+pub struct Ndjson<S>(pub S);
+
+#[cfg(feature = "json")]
+impl<T, S> IntoResponse for Ndjson<S>
+where
+    T: serde::Serialize + 'static,
+    S: futures::Stream<Item = T> + 'static,
+{
+    fn into_response(self) -> HttpResult<types::Response> {
+        let mut resp = http::Response::new(body::ndjson(self.0));
         resp.headers_mut().insert(
             http::header::CONTENT_TYPE,
-            http::HeaderValue::from_static("application/json"),
+            http::HeaderValue::from_static("application/x-ndjson"),
         );
         resp.into_response()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn ndjson_records() {
+        use http_body_util::BodyExt;
+
+        let records = futures::stream::iter([serde_json::json!({"id": 1}), "two".into()]);
+        let body = futures::executor::block_on(body::ndjson(records).collect()).unwrap();
+        assert_eq!(&b"{\"id\":1}\n\"two\"\n"[..], body.to_bytes());
+    }
+
+    #[test]
+    fn proxy_uri_and_headers() {
+        let base = Uri::from_static("https://api.example.com/v1/");
+        assert_eq!(
+            "https://api.example.com/v1/users?page=2",
+            upstream_uri(&base, &Uri::from_static("/users?page=2"))
+                .unwrap()
+                .to_string()
+        );
+        let base = Uri::from_static("http://localhost:8080");
+        assert_eq!(
+            "http://localhost:8080/",
+            upstream_uri(&base, &Uri::from_static("/"))
+                .unwrap()
+                .to_string()
+        );
+        assert!(upstream_uri(&Uri::from_static("/relative"), &base).is_err());
+
+        let mut headers = HeaderMap::new();
+        headers.insert("connection", HeaderValue::from_static("keep-alive, x-hop"));
+        headers.insert("x-hop", HeaderValue::from_static("1"));
+        headers.insert("transfer-encoding", HeaderValue::from_static("chunked"));
+        headers.insert("x-request-id", HeaderValue::from_static("abc"));
+        remove_hop_by_hop_headers(&mut headers);
+        assert_eq!(vec!["x-request-id"], headers.keys().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn error_source_chain() {
+        use std::error::Error as _;
+
+        let io = std::io::Error::other("connection reset");
+        let err = Error::from(anyhow::Error::new(io).context("failed to fetch upstream"));
+        assert_eq!("failed to fetch upstream", err.to_string());
+        let source = err.source().expect("source should be preserved");
+        assert_eq!("connection reset", source.to_string());
+        assert!(source.downcast_ref::<std::io::Error>().is_some());
+    }
+
+    #[test]
+    fn anyhow_status() {
+        let err = anyhow::anyhow!("no user 7")
+            .with_status(StatusCode::NOT_FOUND)
+            .context("loading profile");
+        let Error::Other(err) = Error::from(err) else {
+            panic!("expected Error::Other");
+        };
+        let err = err.downcast::<StatusError>().unwrap();
+        assert_eq!(StatusCode::NOT_FOUND, err.status);
+        assert_eq!("loading profile", err.to_string());
+        assert_eq!("loading profile", err.response_body());
+        assert_eq!("loading profile: no user 7", err.chain());
+
+        let result: anyhow::Result<()> = Err(anyhow::anyhow!("bad input"));
+        let err = result.with_status(StatusCode::BAD_REQUEST).unwrap_err();
+        assert_eq!("bad input", err.to_string());
+        assert_eq!(Some(StatusCode::BAD_REQUEST), attached_status(&err));
+        assert_eq!(None, attached_status(&anyhow::anyhow!("plain")));
+    }
+
+    #[test]
+    fn status_error_bodies() {
+        let body = |status, message: &str| {
+            let Error::Other(err) = Error::with_status(status, message) else {
+                panic!("expected Error::Other");
+            };
+            err.downcast::<StatusError>().unwrap().response_body()
+        };
+        assert_eq!("no such page", body(StatusCode::NOT_FOUND, "no such page"));
+        assert_eq!(
+            "Internal Server Error",
+            body(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "password authentication failed"
+            )
+        );
+        assert_eq!(
+            "Bad Gateway",
+            body(StatusCode::BAD_GATEWAY, "upstream at 10.0.0.5 refused")
+        );
+    }
+
+    #[test]
+    fn status_only_responses() {
+        let accepted = empty_response(StatusCode::ACCEPTED);
+        assert_eq!(StatusCode::ACCEPTED, accepted.status());
+        assert_eq!("0", accepted.headers()[http::header::CONTENT_LENGTH]);
+
+        for status in [StatusCode::NO_CONTENT, StatusCode::NOT_MODIFIED] {
+            let response = empty_response(status);
+            assert!(
+                !response
+                    .headers()
+                    .contains_key(http::header::CONTENT_LENGTH)
+            );
+        }
+    }
+
+    #[test]
+    fn content_length_of_buffered_body() {
+        let mut response = http::Response::builder()
+            .header(http::header::CONTENT_LENGTH, "100")
+            .header(http::header::TRANSFER_ENCODING, "chunked")
+            .body(FullBody::new(bytes::Bytes::from("hello")))
+            .unwrap();
+        normalize_content_length(&mut response);
+        assert_eq!("5", response.headers()[http::header::CONTENT_LENGTH]);
+        assert!(
+            !response
+                .headers()
+                .contains_key(http::header::TRANSFER_ENCODING)
+        );
+
+        let mut response = http::Response::new(EmptyBody::new());
+        normalize_content_length(&mut response);
+        assert_eq!("0", response.headers()[http::header::CONTENT_LENGTH]);
+    }
+
+    #[test]
+    fn content_length_of_streamed_body() {
+        let (_tx, body) = body::stream::<bytes::Bytes>();
+        let mut response = http::Response::builder()
+            .header(http::header::CONTENT_LENGTH, "100")
+            .body(body)
+            .unwrap();
+        normalize_content_length(&mut response);
+        assert!(
+            !response
+                .headers()
+                .contains_key(http::header::CONTENT_LENGTH)
+        );
+    }
+}
@@ -0,0 +1,289 @@
+//! Access logs for HTTP handlers.
+//!
+//! [`logged`] wraps a handler and prints one line per request to stdout,
+//! where Spin collects it with the component's other logs. For more control,
+//! record the request with [`AccessLog::start`] and format the resulting
+//! [`AccessLogEntry`] yourself.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use spin_sdk::http::{IntoResponse, Request, access_log};
+//! use spin_sdk::http_service;
+//!
+//! #[http_service]
+//! async fn handler(request: Request) -> impl IntoResponse {
+//!     access_log::logged(access_log::LogFormat::Common, request, handle).await
+//! }
+//!
+//! async fn handle(request: Request) -> impl IntoResponse {
+//!     // Your logic goes here
+//! }
+//! ```
+
+use super::{FromResponse, HttpResult, IntoResponse, Request, Response};
+use hyperium::{Method, StatusCode, Version, header};
+use std::time::{Duration, Instant, SystemTime};
+
+/// The header in which Spin passes the client's address to the component.
+const CLIENT_ADDR: &str = "spin-client-addr";
+
+/// The format of the lines written by [`logged`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFormat {
+    /// The Common Log Format, as produced by
+    /// [`AccessLogEntry::common_log_format`].
+    Common,
+    /// One JSON object per line, as produced by [`AccessLogEntry::to_json`].
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    Json,
+}
+
+/// Runs `handler` for `request`, then prints an access log line for the
+/// response in the given format.
+///
+/// The line is printed once the handler has produced its response, which
+/// may be before a streamed body has been sent. The size of a streamed
+/// body is therefore only logged if it was declared up front.
+pub async fn logged<F, Fut, R>(
+    format: LogFormat,
+    request: Request,
+    handler: F,
+) -> HttpResult<Response>
+where
+    F: FnOnce(Request) -> Fut,
+    Fut: Future<Output = R>,
+    R: IntoResponse,
+{
+    let log = AccessLog::start(&request);
+    let response = Response::from_response(handler(request).await.into_response()?)?;
+    let entry = log.finish(&response);
+    match format {
+        LogFormat::Common => println!("{}", entry.common_log_format()),
+        #[cfg(feature = "json")]
+        LogFormat::Json => println!("{}", entry.to_json()),
+    }
+    Ok(response)
+}
+
+/// The details of a request, recorded when it arrives so that an
+/// [`AccessLogEntry`] can be produced once it has been answered.
+#[derive(Clone, Debug)]
+pub struct AccessLog {
+    remote_addr: Option<String>,
+    method: Method,
+    target: String,
+    version: Version,
+    user_agent: Option<String>,
+    timestamp: SystemTime,
+    started: Instant,
+}
+
+impl AccessLog {
+    /// Records the details of `request`, and the time it arrived.
+    pub fn start<B>(request: &hyperium::Request<B>) -> Self {
+        let header = |name| {
+            request
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_owned)
+        };
+        let target = request
+            .uri()
+            .path_and_query()
+            .map_or("/", |target| target.as_str());
+        Self {
+            remote_addr: header(CLIENT_ADDR).map(|addr| client_ip(&addr).to_owned()),
+            method: request.method().clone(),
+            target: target.to_owned(),
+            version: request.version(),
+            user_agent: header(header::USER_AGENT.as_str()),
+            timestamp: SystemTime::now(),
+            started: Instant::now(),
+        }
+    }
+
+    /// Completes the record with `response`, measuring how long the request
+    /// took to answer.
+    ///
+    /// The body size is taken from the `Content-Length` header, or from the
+    /// body itself if its size is known.
+    pub fn finish<B: http_body::Body>(self, response: &hyperium::Response<B>) -> AccessLogEntry {
+        let bytes = response
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok()?.parse().ok())
+            .or_else(|| response.body().size_hint().exact());
+        AccessLogEntry {
+            remote_addr: self.remote_addr,
+            method: self.method,
+            target: self.target,
+            version: self.version,
+            user_agent: self.user_agent,
+            timestamp: self.timestamp,
+            status: response.status(),
+            bytes,
+            duration: self.started.elapsed(),
+        }
+    }
+}
+
+/// A completed access log record.
+#[derive(Clone, Debug)]
+pub struct AccessLogEntry {
+    /// The client's IP address, if the host provided it.
+    pub remote_addr: Option<String>,
+    /// The request method.
+    pub method: Method,
+    /// The request path and query.
+    pub target: String,
+    /// The HTTP version of the request.
+    pub version: Version,
+    /// The `User-Agent` header of the request.
+    pub user_agent: Option<String>,
+    /// When the request arrived.
+    pub timestamp: SystemTime,
+    /// The response status.
+    pub status: StatusCode,
+    /// The size of the response body, if known.
+    pub bytes: Option<u64>,
+    /// How long the handler took to produce the response.
+    pub duration: Duration,
+}
+
+impl AccessLogEntry {
+    /// Formats the entry in the Common Log Format, for example
+    /// `192.0.2.1 - - [10/Oct/2000:13:55:36 +0000] "GET /a.gif HTTP/1.1" 200 2326`.
+    ///
+    /// Unknown values are written as `-`.
+    pub fn common_log_format(&self) -> String {
+        let (year, month, day, hour, minute, second) = utc_parts(self.timestamp);
+        const MONTHS: [&str; 12] = [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ];
+        format!(
+            "{} - - [{day:02}/{}/{year}:{hour:02}:{minute:02}:{second:02} +0000] \"{} {} {:?}\" {} {}",
+            self.remote_addr.as_deref().unwrap_or("-"),
+            MONTHS[month as usize - 1],
+            self.method,
+            self.target,
+            self.version,
+            self.status.as_u16(),
+            self.bytes.map_or("-".to_owned(), |b| b.to_string()),
+        )
+    }
+
+    /// Converts the entry to a JSON object with `timestamp` (RFC 3339, UTC),
+    /// `remote_addr`, `method`, `target`, `version`, `status`, `bytes`,
+    /// `duration_ms` and `user_agent` fields. Unknown values are `null`.
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn to_json(&self) -> serde_json::Value {
+        let (year, month, day, hour, minute, second) = utc_parts(self.timestamp);
+        serde_json::json!({
+            "timestamp": format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}Z"),
+            "remote_addr": self.remote_addr,
+            "method": self.method.as_str(),
+            "target": self.target,
+            "version": format!("{:?}", self.version),
+            "status": self.status.as_u16(),
+            "bytes": self.bytes,
+            "duration_ms": self.duration.as_secs_f64() * 1000.0,
+            "user_agent": self.user_agent,
+        })
+    }
+}
+
+/// The IP address part of a `host:port` client address.
+fn client_ip(addr: &str) -> &str {
+    match addr.parse::<std::net::SocketAddr>() {
+        Ok(_) => addr.rsplit_once(':').map_or(addr, |(ip, _)| {
+            ip.trim_start_matches('[').trim_end_matches(']')
+        }),
+        Err(_) => addr,
+    }
+}
+
+/// Splits a time into UTC year, month, day, hour, minute and second.
+fn utc_parts(time: SystemTime) -> (i64, u32, u32, u32, u32, u32) {
+    let secs = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs()) as i64;
+    let (days, secs_of_day) = (secs.div_euclid(86_400), secs.rem_euclid(86_400) as u32);
+
+    // Converts days since 1970-01-01 to a civil date, counting years from
+    // March so that the leap day falls at the end of the year.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn entry() -> AccessLogEntry {
+        let request = hyperium::Request::get("/a.gif?size=large")
+            .header(CLIENT_ADDR, "192.0.2.1:51234")
+            .header("user-agent", "curl/8.0")
+            .body(())
+            .unwrap();
+        let mut log = AccessLog::start(&request);
+        log.timestamp = SystemTime::UNIX_EPOCH + Duration::from_secs(971_186_136);
+        let response = hyperium::Response::builder()
+            .status(StatusCode::OK)
+            .body(http_body_util::Full::new(bytes::Bytes::from_static(
+                b"GIF89a",
+            )))
+            .unwrap();
+        log.finish(&response)
+    }
+
+    #[test]
+    fn common_log_format() {
+        assert_eq!(
+            "192.0.2.1 - - [10/Oct/2000:13:55:36 +0000] \"GET /a.gif?size=large HTTP/1.1\" 200 6",
+            entry().common_log_format()
+        );
+
+        let mut entry = entry();
+        entry.remote_addr = None;
+        entry.bytes = None;
+        entry.timestamp = SystemTime::UNIX_EPOCH + Duration::from_secs(951_782_400);
+        assert_eq!(
+            "- - - [29/Feb/2000:00:00:00 +0000] \"GET /a.gif?size=large HTTP/1.1\" 200 -",
+            entry.common_log_format()
+        );
+        assert_eq!("2001:db8::1", client_ip("[2001:db8::1]:443"));
+        assert_eq!("not an address", client_ip("not an address"));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_format() {
+        let json = entry().to_json();
+        assert_eq!("2000-10-10T13:55:36Z", json["timestamp"]);
+        assert_eq!("192.0.2.1", json["remote_addr"]);
+        assert_eq!(200, json["status"]);
+        assert_eq!(6, json["bytes"]);
+        assert_eq!("curl/8.0", json["user_agent"]);
+    }
+}
@@ -7,12 +7,19 @@
 //! These helpers make it easier to transform low-level streaming body types
 //! into higher-level forms (e.g., [`bytes::Bytes`]) for simplified data handling.
 
-use bytes::Bytes;
+use super::{ErrorCodeExt, FullBody};
+use bytes::{BufMut, Bytes, BytesMut};
 use futures::{
-    StreamExt,
+    StreamExt, TryStreamExt,
     channel::mpsc::{Sender, channel},
 };
 use http_body_util::{BodyDataStream, BodyExt};
+use hyperium::HeaderMap;
+use std::{
+    pin::Pin,
+    task::{Context, Poll, ready},
+    time::Duration,
+};
 use wasip3::{
     http::types::ErrorCode,
     http_compat::{IncomingBody, IncomingMessage},
@@ -34,12 +41,79 @@ pub trait IncomingBodyExt {
     where
         Self: Sized;
 
+    /// Convert this [`IncomingBody`] into a plain stream of data chunks.
+    ///
+    /// This is a simpler alternative to [`IncomingBodyExt::stream`] for code
+    /// that only needs to loop over the chunks as they arrive. The stream
+    /// reads the body's frames directly and yields each data frame as it is
+    /// received; any trailers are discarded.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use futures::StreamExt;
+    /// use spin_sdk::http::{Request, body::IncomingBodyExt};
+    ///
+    /// # async fn run(req: Request) -> anyhow::Result<()> {
+    /// let mut chunks = req.into_body().into_byte_stream();
+    /// while let Some(chunk) = chunks.next().await {
+    ///     println!("received {} bytes", chunk?.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn into_byte_stream(self) -> impl futures::Stream<Item = Result<Bytes, ErrorCode>> + Unpin
+    where
+        Self: Sized;
+
     /// Consume this [`IncomingBody`] and collect it into a single [`Bytes`] buffer.
     ///
     /// This method reads the entire body asynchronously and returns the
     /// concatenated contents. It is best suited for small or bounded-size
     /// payloads where holding all data in memory is acceptable.
     async fn bytes(self) -> Result<Bytes, ErrorCode>;
+
+    /// Collect the body like [`IncomingBodyExt::bytes`], but give up if it
+    /// has not been received in full within `timeout`.
+    ///
+    /// This protects a handler from an upstream that sends its body very
+    /// slowly, or stops sending part way through. On timeout, reading stops
+    /// and [`ErrorCode::ConnectionReadTimeout`] is returned.
+    ///
+    /// To bound the size of the body as well, race [`snapshot_response`] or
+    /// [`snapshot_request`] against the deadline with
+    /// [`crate::time::timeout`] instead.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use spin_sdk::http::body::IncomingBodyExt;
+    /// use std::time::Duration;
+    ///
+    /// # async fn run() -> anyhow::Result<()> {
+    /// let response = spin_sdk::http::get("https://example.com").await?;
+    /// let body = response
+    ///     .into_body()
+    ///     .bytes_with_timeout(Duration::from_secs(5))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    async fn bytes_with_timeout(self, timeout: Duration) -> Result<Bytes, ErrorCode>;
+
+    /// Convert this [`IncomingBody`] into a [`futures::AsyncRead`].
+    ///
+    /// This allows the body to be passed to parsers and other libraries that
+    /// consume a reader rather than a stream of chunks. Data frames are
+    /// buffered as needed to satisfy each read, and the reader reaches
+    /// end-of-file once all data has been read; any trailers are discarded.
+    /// Body errors are reported as [`std::io::Error`]s.
+    ///
+    /// Libraries that require a `tokio::io::AsyncRead` can use the reader via
+    /// the `tokio_util::compat` adapters.
+    fn into_async_read(self) -> impl futures::AsyncRead + Unpin
+    where
+        Self: Sized;
 }
 
 impl<T: IncomingMessage> IncomingBodyExt for IncomingBody<T> {
@@ -51,9 +125,47 @@ impl<T: IncomingMessage> IncomingBodyExt for IncomingBody<T> {
         BodyDataStream::new(self)
     }
 
+    /// Convert this [`IncomingBody`] into a plain stream of data chunks.
+    fn into_byte_stream(mut self) -> impl futures::Stream<Item = Result<Bytes, ErrorCode>> + Unpin
+    where
+        Self: Sized,
+    {
+        use http_body::Body;
+
+        futures::stream::poll_fn(move |cx| {
+            loop {
+                match ready!(Pin::new(&mut self).poll_frame(cx)?) {
+                    Some(frame) => {
+                        if let Ok(data) = frame.into_data() {
+                            return Poll::Ready(Some(Ok(data)));
+                        }
+                    }
+                    None => return Poll::Ready(None),
+                }
+            }
+        })
+    }
+
     /// Collect the [`IncomingBody`] into a single [`Bytes`] buffer.
     async fn bytes(self) -> Result<Bytes, ErrorCode> {
-        self.collect().await.map(|c| c.to_bytes())
+        collect_sized(self).await
+    }
+
+    /// Collect the [`IncomingBody`], giving up after `timeout`.
+    async fn bytes_with_timeout(self, timeout: Duration) -> Result<Bytes, ErrorCode> {
+        crate::time::timeout(timeout, self.bytes())
+            .await
+            .unwrap_or(Err(ErrorCode::ConnectionReadTimeout))
+    }
+
+    /// Convert this [`IncomingBody`] into a [`futures::AsyncRead`].
+    fn into_async_read(self) -> impl futures::AsyncRead + Unpin
+    where
+        Self: Sized,
+    {
+        self.stream()
+            .map_err(std::io::Error::other)
+            .into_async_read()
     }
 }
 
@@ -100,3 +212,491 @@ pub fn stream_any<T>(
     let stm = rx.map(move |value| Ok(http_body::Frame::data(f(value))));
     (tx, http_body_util::StreamBody::new(stm))
 }
+
+/// Create a body from a stream of chunks.
+///
+/// Each chunk is sent as its own data frame. The stream is only polled as
+/// the body is read, so a slow client holds back the source rather than
+/// buffering it in memory. If the stream yields an error, the body ends with
+/// that error.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use spin_sdk::http::Response;
+/// # use spin_sdk::http::body::from_stream;
+/// let chunks = futures::stream::iter(["hello, ", "world"].map(Ok::<_, std::io::Error>));
+/// let response = Response::new(from_stream(chunks));
+/// ```
+pub fn from_stream<S, T, E>(chunks: S) -> impl http_body::Body<Data = Bytes, Error = anyhow::Error>
+where
+    S: futures::Stream<Item = Result<T, E>>,
+    T: Into<Bytes>,
+    E: Into<anyhow::Error>,
+{
+    let frames = chunks.map(|chunk| match chunk {
+        Ok(data) => Ok(http_body::Frame::data(data.into())),
+        Err(e) => Err(e.into()),
+    });
+    http_body_util::StreamBody::new(frames)
+}
+
+/// Create a body that reads from an [`AsyncRead`](futures::AsyncRead), such
+/// as a file or a decompressor.
+///
+/// Data is read in chunks of up to 8 KiB as the body is consumed, so the
+/// reader is never more than one chunk ahead of the client. The body ends
+/// when the reader reaches end of file, or with an error if a read fails.
+pub fn from_reader<R>(reader: R) -> impl http_body::Body<Data = Bytes, Error = anyhow::Error>
+where
+    R: futures::AsyncRead + Unpin,
+{
+    const CHUNK_SIZE: usize = 8 * 1024;
+
+    let chunks = futures::stream::try_unfold(reader, |mut reader| async move {
+        let mut buf = vec![0; CHUNK_SIZE];
+        let n = futures::AsyncReadExt::read(&mut reader, &mut buf).await?;
+        if n == 0 {
+            return Ok::<_, std::io::Error>(None);
+        }
+        buf.truncate(n);
+        Ok(Some((Bytes::from(buf), reader)))
+    });
+    from_stream(chunks)
+}
+
+/// Create a newline-delimited JSON (NDJSON) body from a stream of values.
+///
+/// Each value is serialized as JSON followed by `\n` and sent as its own data
+/// frame, so records reach the client as they are produced. If a value fails
+/// to serialize, the body ends with an error.
+///
+/// Use [`Ndjson`](super::Ndjson) to also set the `Content-Type` header of a
+/// response, or [`ndjson_stream`] to write records through a channel.
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+pub fn ndjson<T, S>(values: S) -> impl http_body::Body<Data = Bytes, Error = anyhow::Error>
+where
+    T: serde::Serialize,
+    S: futures::Stream<Item = T>,
+{
+    let frames = values.map(|value| {
+        let mut record = serde_json::to_vec(&value)?;
+        record.push(b'\n');
+        Ok(http_body::Frame::data(Bytes::from(record)))
+    });
+    http_body_util::StreamBody::new(frames)
+}
+
+/// Create a newline-delimited JSON (NDJSON) body, with a `Sender` for
+/// writing records to the body.
+///
+/// This is the channel counterpart of [`ndjson`]: each value sent is
+/// serialized as one line of JSON. The body ends when the sender is dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use spin_sdk::http::Response;
+/// # use spin_sdk::http::body::ndjson_stream;
+/// use futures::SinkExt;
+///
+/// #[derive(serde::Serialize)]
+/// struct Record { id: u32 }
+///
+/// let (mut tx, body) = ndjson_stream::<Record>();
+///
+/// spin_sdk::wasip3::spawn(async move {
+///     for id in 0..10000 {
+///         if tx.send(Record { id }).await.is_err() {
+///             break;
+///         }
+///     }
+/// });
+///
+/// let response = Response::builder()
+///     .header("content-type", "application/x-ndjson")
+///     .body(body);
+/// ```
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+pub fn ndjson_stream<T: serde::Serialize>() -> (
+    Sender<T>,
+    impl http_body::Body<Data = Bytes, Error = anyhow::Error>,
+) {
+    let (tx, rx) = channel::<T>(1024);
+    (tx, ndjson(rx))
+}
+
+/// Create a streaming body, with a [`BodySink`] for writing to the body.
+///
+/// The [`BodySink`] implements both [`futures::AsyncWrite`] and
+/// [`futures::Sink<Bytes>`], so code that writes to a generic writer (such
+/// as a serializer) can stream directly into an HTTP request or response.
+/// The body ends when the sink is closed or dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use spin_sdk::http::Response;
+/// # use spin_sdk::http::body::writer;
+/// use futures::AsyncWriteExt;
+///
+/// let (mut sink, body) = writer();
+///
+/// spin_sdk::wasip3::spawn(async move {
+///     for i in 0..10000 {
+///         if sink.write_all(format!("{i}\n").as_bytes()).await.is_err() {
+///             break;
+///         }
+///     }
+///     _ = sink.close().await;
+/// });
+///
+/// let response = Response::new(body);
+/// ```
+pub fn writer() -> (
+    BodySink,
+    impl http_body::Body<Data = Bytes, Error = anyhow::Error>,
+) {
+    let (tx, body) = stream::<Bytes>();
+    (BodySink(tx), body)
+}
+
+/// The writing end of a body created by [`writer`].
+///
+/// Flushing waits until previously written data has been accepted by the
+/// body, and closing the sink ends the body.
+pub struct BodySink(Sender<Bytes>);
+
+fn body_closed(_: futures::channel::mpsc::SendError) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::BrokenPipe, "body receiver was dropped")
+}
+
+impl futures::Sink<Bytes> for BodySink {
+    type Error = std::io::Error;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.0.poll_ready(cx).map_err(body_closed)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: Bytes) -> Result<(), Self::Error> {
+        self.0.start_send(item).map_err(body_closed)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.0).poll_flush(cx).map_err(body_closed)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.0).poll_close(cx).map_err(body_closed)
+    }
+}
+
+impl futures::AsyncWrite for BodySink {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        ready!(self.0.poll_ready(cx)).map_err(body_closed)?;
+        self.0
+            .start_send(Bytes::copy_from_slice(buf))
+            .map_err(body_closed)?;
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        futures::Sink::<Bytes>::poll_flush(self, cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        futures::Sink::<Bytes>::poll_close(self, cx)
+    }
+}
+
+/// Appends `trailers` to `body`, to be sent once all of its data has been sent.
+///
+/// When a request or response is sent, any trailers produced by its body are
+/// written as the trailers of the WASI message. This is how, for example, a
+/// gRPC service reports `grpc-status` after streaming its messages.
+///
+/// This is for trailers that are known up front. For trailers that are only
+/// known once the data has been produced, use
+/// [`BodyExt::with_trailers`](http_body_util::BodyExt::with_trailers) with a
+/// future that resolves to them.
+///
+/// # Examples
+///
+/// ```no_run
+/// use spin_sdk::http::{HeaderMap, HeaderValue, Response};
+/// use spin_sdk::http::body::with_trailers;
+///
+/// let mut trailers = HeaderMap::new();
+/// trailers.insert("grpc-status", HeaderValue::from_static("0"));
+///
+/// let response = Response::new(with_trailers(String::from("payload"), trailers));
+/// ```
+pub fn with_trailers<B>(
+    body: B,
+    trailers: HeaderMap,
+) -> impl http_body::Body<Data = B::Data, Error = B::Error>
+where
+    B: http_body::Body,
+{
+    body.with_trailers(std::future::ready(Some(Ok(trailers))))
+}
+
+/// Reads the whole body of `request` into memory, returning the body bytes
+/// along with an equivalent request whose body replays them.
+///
+/// This "buffer and replay" pattern lets code inspect a complete body, for
+/// example to log it or verify a signature, and still pass the request on
+/// to a handler that consumes the body. At most `limit` bytes are buffered;
+/// a larger body fails with [`ErrorCode::HttpRequestBodySize`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use spin_sdk::http::Request;
+/// use spin_sdk::http::body::snapshot_request;
+///
+/// # async fn run(request: Request) -> Result<(), spin_sdk::http::Error> {
+/// let (body, request) = snapshot_request(request, 64 * 1024).await?;
+/// println!("{} {} ({} bytes)", request.method(), request.uri(), body.len());
+/// # Ok(())
+/// # }
+/// ```
+pub async fn snapshot_request<B>(
+    request: hyperium::Request<B>,
+    limit: usize,
+) -> Result<(Bytes, hyperium::Request<FullBody<Bytes>>), ErrorCode>
+where
+    B: http_body::Body,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    let (parts, body) = request.into_parts();
+    let bytes = collect_limited(body, limit, ErrorCode::HttpRequestBodySize).await?;
+    let request = hyperium::Request::from_parts(parts, FullBody::new(bytes.clone()));
+    Ok((bytes, request))
+}
+
+/// A request whose body is held in memory.
+///
+/// Unlike a request with a streaming body, this can be cloned, so the same
+/// request can be sent several times, for example when retrying a `POST`.
+pub type BufferedRequest = hyperium::Request<FullBody<Bytes>>;
+
+/// Reads the whole body of `request` into memory, so that the request can be
+/// cloned and sent more than once.
+///
+/// A streaming body is fully buffered by this, so it is only suitable for
+/// bodies that fit comfortably in memory. At most `limit` bytes are
+/// buffered; a larger body fails with [`ErrorCode::HttpRequestBodySize`].
+/// To also inspect the body bytes, use [`snapshot_request`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use spin_sdk::http::{Request, send};
+/// use spin_sdk::http::body::buffer_request;
+///
+/// # async fn run(request: Request) -> Result<(), spin_sdk::http::Error> {
+/// let request = buffer_request(request, 64 * 1024).await?;
+/// let mut response = send(request.clone()).await?;
+/// if response.status().is_server_error() {
+///     response = send(request).await?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn buffer_request<B>(
+    request: hyperium::Request<B>,
+    limit: usize,
+) -> Result<BufferedRequest, ErrorCode>
+where
+    B: http_body::Body,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    snapshot_request(request, limit)
+        .await
+        .map(|(_, request)| request)
+}
+
+/// Reads the whole body of `response` into memory, returning the body bytes
+/// along with an equivalent response whose body replays them.
+///
+/// This is the response counterpart of [`snapshot_request`]. At most `limit`
+/// bytes are buffered; a larger body fails with
+/// [`ErrorCode::HttpResponseBodySize`].
+pub async fn snapshot_response<B>(
+    response: hyperium::Response<B>,
+    limit: usize,
+) -> Result<(Bytes, hyperium::Response<FullBody<Bytes>>), ErrorCode>
+where
+    B: http_body::Body,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    let (parts, body) = response.into_parts();
+    let bytes = collect_limited(body, limit, ErrorCode::HttpResponseBodySize).await?;
+    let response = hyperium::Response::from_parts(parts, FullBody::new(bytes.clone()));
+    Ok((bytes, response))
+}
+
+async fn collect_limited<B>(
+    body: B,
+    limit: usize,
+    too_large: fn(Option<u64>) -> ErrorCode,
+) -> Result<Bytes, ErrorCode>
+where
+    B: http_body::Body,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    match collect_sized(http_body_util::Limited::new(body, limit)).await {
+        Ok(bytes) => Ok(bytes),
+        Err(e) if e.is::<http_body_util::LengthLimitError>() => Err(too_large(None)),
+        Err(e) => match e.downcast::<ErrorCode>() {
+            Ok(code) => Err(*code),
+            Err(e) => Err(ErrorCode::internal(e)),
+        },
+    }
+}
+
+/// Collects the data frames of `body` into one buffer, discarding any
+/// trailers.
+///
+/// The buffer is allocated up front from the body's size hint, so that a
+/// small body does not have to grow as frames arrive. See
+/// [`initial_capacity`] for how much is reserved.
+async fn collect_sized<B: http_body::Body>(body: B) -> Result<Bytes, B::Error> {
+    let mut body = std::pin::pin!(body);
+    let mut buf = BytesMut::with_capacity(initial_capacity(&body.size_hint()));
+    while let Some(frame) = body.frame().await {
+        if let Ok(data) = frame?.into_data() {
+            buf.put(data);
+        }
+    }
+    Ok(buf.freeze())
+}
+
+/// The number of bytes [`collect_sized`] reserves before any data has
+/// arrived.
+///
+/// For an incoming body, the upper bound of the hint is the `Content-Length`
+/// sent by the client, which may be far larger than the body that follows.
+/// At most [`MAX_PREALLOCATION`] bytes are reserved on its say-so; a larger
+/// body grows the buffer as its data arrives.
+fn initial_capacity(hint: &http_body::SizeHint) -> usize {
+    hint.upper().unwrap_or(hint.lower()).min(MAX_PREALLOCATION) as usize
+}
+
+/// The most memory [`collect_sized`] reserves before any data has arrived.
+const MAX_PREALLOCATION: u64 = 64 * 1024;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::executor::block_on;
+
+    /// A body with no `Content-Length`, delivered in chunks and ended by
+    /// trailers, as with `Transfer-Encoding: chunked`.
+    fn chunked_body() -> impl http_body::Body<Data = Bytes, Error = ErrorCode> {
+        let mut trailers = HeaderMap::new();
+        trailers.insert("x-checksum", "abc".parse().unwrap());
+        let frames = futures::stream::iter([
+            Ok(http_body::Frame::data(Bytes::from("hello, "))),
+            Ok(http_body::Frame::data(Bytes::from("chunked "))),
+            Ok(http_body::Frame::data(Bytes::from("world"))),
+            Ok(http_body::Frame::trailers(trailers)),
+        ]);
+        http_body_util::StreamBody::new(frames)
+    }
+
+    #[test]
+    fn collect_chunked_body() {
+        use http_body::Body as _;
+
+        assert_eq!(None, chunked_body().size_hint().exact());
+        let body = block_on(collect_sized(chunked_body())).unwrap();
+        assert_eq!("hello, chunked world", body);
+
+        let request = hyperium::Request::new(chunked_body());
+        let (body, request) = block_on(snapshot_request(request, 1024)).unwrap();
+        assert_eq!("hello, chunked world", body);
+        let replayed = block_on(request.into_body().collect()).unwrap();
+        assert_eq!(body, replayed.to_bytes());
+
+        let response = hyperium::Response::new(chunked_body());
+        assert!(matches!(
+            block_on(snapshot_response(response, 8)),
+            Err(ErrorCode::HttpResponseBodySize(None))
+        ));
+    }
+
+    #[test]
+    fn stream_and_reader_bodies() {
+        let chunks = futures::stream::iter(["hello, ", "world"].map(Ok::<_, std::io::Error>));
+        let body = block_on(from_stream(chunks).collect()).unwrap();
+        assert_eq!("hello, world", body.to_bytes());
+
+        let failing =
+            futures::stream::iter([Ok("partial"), Err(std::io::Error::other("source failed"))]);
+        let err = block_on(from_stream(failing).collect()).unwrap_err();
+        assert_eq!("source failed", err.to_string());
+
+        let data = vec![7u8; 20_000];
+        let body = block_on(from_reader(futures::io::Cursor::new(data.clone())).collect()).unwrap();
+        assert_eq!(data, body.to_bytes());
+    }
+
+    #[test]
+    fn collect_to_size_hint() {
+        /// A body that declares its length up front, as with
+        /// `Content-Length`.
+        struct Declared<B>(B, u64);
+
+        impl<B: http_body::Body + Unpin> http_body::Body for Declared<B> {
+            type Data = B::Data;
+            type Error = B::Error;
+
+            fn poll_frame(
+                mut self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<Option<Result<http_body::Frame<Self::Data>, Self::Error>>> {
+                Pin::new(&mut self.0).poll_frame(cx)
+            }
+
+            fn size_hint(&self) -> http_body::SizeHint {
+                http_body::SizeHint::with_exact(self.1)
+            }
+        }
+
+        let body = block_on(collect_sized(Declared(chunked_body(), 20))).unwrap();
+        assert_eq!("hello, chunked world", body);
+
+        let body = block_on(collect_sized(chunked_body())).unwrap();
+        assert_eq!("hello, chunked world", body);
+
+        let body = block_on(collect_sized(Declared(chunked_body(), u64::MAX))).unwrap();
+        assert_eq!("hello, chunked world", body);
+
+        assert_eq!(20, initial_capacity(&http_body::SizeHint::with_exact(20)));
+        assert_eq!(0, initial_capacity(&http_body::SizeHint::new()));
+        let forged = http_body::SizeHint::with_exact(16 * 1024 * 1024);
+        assert_eq!(MAX_PREALLOCATION as usize, initial_capacity(&forged));
+    }
+
+    #[test]
+    fn buffered_request() {
+        let request = hyperium::Request::post("/upload")
+            .header("x-attempt", "1")
+            .body(chunked_body())
+            .unwrap();
+        let request = block_on(buffer_request(request, 1024)).unwrap();
+        for attempt in [request.clone(), request] {
+            assert_eq!("1", attempt.headers()["x-attempt"]);
+            let body = block_on(attempt.into_body().collect()).unwrap();
+            assert_eq!("hello, chunked world", body.to_bytes());
+        }
+    }
+}
@@ -0,0 +1,862 @@
+//! Response body compression.
+//!
+//! Wrap a handler's response in [`Compressed`] to compress it according to
+//! the `Accept-Encoding` header of the request. Gzip is always available;
+//! Brotli is used in preference to gzip when the `brotli` feature is enabled
+//! and the client accepts it.
+//!
+//! Responses are left uncompressed when the client does not accept a
+//! supported encoding, when the response already has a `Content-Encoding`,
+//! when its content type is already compressed (such as images or archives),
+//! or when its body is known to be smaller than [`MIN_COMPRESS_SIZE`]. The
+//! size of a string, byte buffer or [`Json`](super::Json) body is always
+//! known; see [`IntoCompressible`] for other responses. When a response is
+//! compressed, a strong `ETag` is made weak, as the encoded body differs from
+//! the one the tag describes.
+//!
+//! Use [`Compressed::with_config`] and a [`CompressionConfig`] to change the
+//! encoding preference, the compression level, the minimum size, how often
+//! streamed output is flushed, or to skip further content types.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use spin_sdk::http::{IntoResponse, Request};
+//! use spin_sdk::http::compression::Compressed;
+//! use spin_sdk::http_service;
+//!
+//! #[http_service]
+//! async fn handler(req: Request) -> impl IntoResponse {
+//!     let body = "a large, compressible response ".repeat(100);
+//!     Compressed::new(req.headers(), body)
+//! }
+//! ```
+
+use super::{ErrorCodeExt, FromResponse, FullBody, HeaderExt, HttpResult, IntoResponse, Response};
+use bytes::Bytes;
+use http_body_util::Either;
+use hyperium::{HeaderMap, HeaderValue, header};
+use std::{
+    io::Write,
+    pin::Pin,
+    task::{Context, Poll, ready},
+};
+use wasip3::{http::types, http_compat::IncomingBody};
+
+/// Bodies known to be smaller than this many bytes are not compressed, as
+/// the saving would be outweighed by the encoding overhead.
+pub const MIN_COMPRESS_SIZE: u64 = 1024;
+
+/// The compressed output of a streamed body is flushed to the client each
+/// time this many bytes of input have arrived since the last flush, and at
+/// the end of the body.
+pub const FLUSH_THRESHOLD: usize = 16 * 1024;
+
+/// A content coding that responses can be compressed with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// The `gzip` content coding.
+    Gzip,
+    /// The `br` (Brotli) content coding.
+    #[cfg(feature = "brotli")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "brotli")))]
+    Brotli,
+}
+
+impl Encoding {
+    /// The token used for this encoding in `Accept-Encoding` and
+    /// `Content-Encoding` headers.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Encoding::Gzip => "gzip",
+            #[cfg(feature = "brotli")]
+            Encoding::Brotli => "br",
+        }
+    }
+
+    /// Chooses the preferred supported encoding from a request's
+    /// `Accept-Encoding` header, if the client accepts any of them.
+    ///
+    /// Encodings with a quality value of zero are treated as refused, and a
+    /// `*` entry accepts any encoding not otherwise listed.
+    pub fn negotiate(request_headers: &HeaderMap) -> Option<Self> {
+        negotiate(request_headers, Self::SUPPORTED)
+    }
+
+    /// Supported encodings, most preferred first.
+    const SUPPORTED: &[Self] = &[
+        #[cfg(feature = "brotli")]
+        Encoding::Brotli,
+        Encoding::Gzip,
+    ];
+}
+
+/// Chooses the encoding the client accepts with the highest quality, taking
+/// the first of `candidates` when several are accepted equally.
+fn negotiate(request_headers: &HeaderMap, candidates: &[Encoding]) -> Option<Encoding> {
+    let mut preferred: Option<(Encoding, f32)> = None;
+    for candidate in candidates {
+        let Some(quality) = accepted_quality(request_headers, candidate.as_str()) else {
+            continue;
+        };
+        if quality > 0.0 && preferred.is_none_or(|(_, best)| quality > best) {
+            preferred = Some((*candidate, quality));
+        }
+    }
+    preferred.map(|(encoding, _)| encoding)
+}
+
+/// How much effort to spend compressing a response.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CompressionLevel {
+    /// Compress as quickly as possible: gzip level 1, Brotli quality 1.
+    /// Suits dynamic responses such as JSON.
+    Fastest,
+    /// A balance of speed and size: gzip level 6, Brotli quality 5.
+    #[default]
+    Default,
+    /// Compress as small as possible: gzip level 9, Brotli quality 11.
+    /// Suits responses that are generated once and cached.
+    Best,
+}
+
+/// Settings for [`Compressed::with_config`].
+///
+/// The default prefers Brotli (with the `brotli` feature) over gzip, uses
+/// [`CompressionLevel::Default`], skips bodies smaller than
+/// [`MIN_COMPRESS_SIZE`], flushes every [`FLUSH_THRESHOLD`] bytes, and skips
+/// the already-compressed content types described in the
+/// [module documentation](self).
+///
+/// # Examples
+///
+/// ```ignore
+/// use spin_sdk::http::compression::{CompressionConfig, CompressionLevel, Encoding};
+///
+/// let config = CompressionConfig::default()
+///     .encodings([Encoding::Gzip])
+///     .level(CompressionLevel::Fastest)
+///     .skip_content_type("text/event-stream");
+/// ```
+#[derive(Clone, Debug)]
+pub struct CompressionConfig {
+    encodings: Vec<Encoding>,
+    level: CompressionLevel,
+    min_size: u64,
+    flush_threshold: usize,
+    skip_content_types: Vec<String>,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            encodings: Encoding::SUPPORTED.to_vec(),
+            level: CompressionLevel::Default,
+            min_size: MIN_COMPRESS_SIZE,
+            flush_threshold: FLUSH_THRESHOLD,
+            skip_content_types: Vec::new(),
+        }
+    }
+}
+
+impl CompressionConfig {
+    /// Sets the encodings that may be used, most preferred first.
+    ///
+    /// The client's quality values still take precedence; the order only
+    /// decides between encodings the client accepts equally. An empty list
+    /// disables compression.
+    pub fn encodings(mut self, encodings: impl IntoIterator<Item = Encoding>) -> Self {
+        self.encodings = encodings.into_iter().collect();
+        self
+    }
+
+    /// Sets the compression level.
+    pub fn level(mut self, level: CompressionLevel) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Sets the body size, in bytes, below which responses are not
+    /// compressed. Bodies of unknown size are always compressed.
+    pub fn min_size(mut self, min_size: u64) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    /// Sets how many bytes of input are compressed before the output is
+    /// flushed to the client.
+    ///
+    /// Flushing less often compresses better. A streamed response whose
+    /// chunks must reach the client promptly, such as NDJSON progress
+    /// updates, can use a small threshold; 0 flushes after every chunk.
+    pub fn flush_threshold(mut self, bytes: usize) -> Self {
+        self.flush_threshold = bytes;
+        self
+    }
+
+    /// Leaves responses of the given content type uncompressed, in addition
+    /// to the types that are already compressed.
+    ///
+    /// The pattern is matched as by [`MediaType::matches`](super::MediaType::matches),
+    /// so `text/event-stream`, `text/*` and `*/*` are all accepted.
+    pub fn skip_content_type(mut self, pattern: impl Into<String>) -> Self {
+        self.skip_content_types.push(pattern.into());
+        self
+    }
+
+    /// Chooses the preferred configured encoding from a request's
+    /// `Accept-Encoding` header, if the client accepts any of them.
+    pub fn negotiate(&self, request_headers: &HeaderMap) -> Option<Encoding> {
+        negotiate(request_headers, &self.encodings)
+    }
+
+    fn skips<B>(&self, response: &hyperium::Response<B>) -> bool {
+        if self.skip_content_types.is_empty() {
+            return false;
+        }
+        response.content_type().is_some_and(|content_type| {
+            self.skip_content_types
+                .iter()
+                .any(|pattern| content_type.matches(pattern))
+        })
+    }
+}
+
+/// Returns the quality with which `coding` is accepted, or `None` if the
+/// header does not mention it (explicitly or via `*`).
+fn accepted_quality(headers: &HeaderMap, coding: &str) -> Option<f32> {
+    let mut wildcard = None;
+    for value in headers.get_all(header::ACCEPT_ENCODING) {
+        let Ok(value) = value.to_str() else {
+            continue;
+        };
+        for entry in value.split(',') {
+            let mut params = entry.split(';');
+            let name = params.next().unwrap_or_default().trim();
+            let quality = params
+                .filter_map(|p| p.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if name.eq_ignore_ascii_case(coding) {
+                return Some(quality);
+            }
+            if name == "*" {
+                wildcard = Some(quality);
+            }
+        }
+    }
+    wildcard
+}
+
+/// Content types whose bodies are already compressed.
+fn is_precompressed(content_type: &str) -> bool {
+    let essence = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    match essence.split_once('/') {
+        Some(("image", subtype)) => subtype != "svg+xml",
+        Some(("audio" | "video", _)) => true,
+        Some(("font", subtype)) => subtype == "woff" || subtype == "woff2",
+        Some(("application", subtype)) => matches!(
+            subtype,
+            "gzip" | "zip" | "zstd" | "x-7z-compressed" | "x-bzip2" | "x-xz" | "x-rar-compressed"
+        ),
+        _ => false,
+    }
+}
+
+/// A response wrapper that compresses the body of the inner response.
+///
+/// The encoding is negotiated from the request headers when the wrapper is
+/// created. See the [module documentation](self) for when compression is
+/// skipped.
+pub struct Compressed<T> {
+    inner: T,
+    encoding: Option<Encoding>,
+    config: CompressionConfig,
+}
+
+impl<T> Compressed<T> {
+    /// Wraps `inner`, negotiating the encoding from the request's
+    /// `Accept-Encoding` header.
+    pub fn new(request_headers: &HeaderMap, inner: T) -> Self {
+        Self::with_config(request_headers, inner, CompressionConfig::default())
+    }
+
+    /// Wraps `inner`, compressing it according to `config`.
+    pub fn with_config(request_headers: &HeaderMap, inner: T, config: CompressionConfig) -> Self {
+        Self {
+            inner,
+            encoding: config.negotiate(request_headers),
+            config,
+        }
+    }
+}
+
+impl<T: IntoCompressible> Compressed<T> {
+    /// Converts the inner response and, if it should be compressed, replaces
+    /// its body with a compressing one.
+    #[allow(clippy::type_complexity)]
+    fn into_http(self) -> HttpResult<hyperium::Response<Either<CompressedBody<T::Body>, T::Body>>> {
+        let response = self.inner.into_compressible()?;
+        let Some(encoding) = self
+            .encoding
+            .filter(|_| should_compress(&response, &self.config))
+        else {
+            return Ok(response.map(Either::Right));
+        };
+
+        let mut response = response;
+        let headers = response.headers_mut();
+        headers.remove(header::CONTENT_LENGTH);
+        headers.insert(
+            header::CONTENT_ENCODING,
+            HeaderValue::from_static(encoding.as_str()),
+        );
+        headers.append(header::VARY, HeaderValue::from_static("accept-encoding"));
+        weaken_etag(headers);
+
+        Ok(response.map(|body| Either::Left(CompressedBody::new(body, encoding, &self.config))))
+    }
+}
+
+impl<T: IntoCompressible> IntoResponse for Compressed<T> {
+    fn into_response(self) -> HttpResult<types::Response> {
+        self.into_http()?.into_response()
+    }
+}
+
+/// A response that can be wrapped in [`Compressed`].
+///
+/// The response is converted to an [`http::Response`](hyperium::Response)
+/// before deciding whether to compress it, so that the size of a buffered
+/// body is still known. This is implemented for strings, byte buffers,
+/// `http::Response`s and `(StatusCode, body)` pairs, [`Json`](super::Json)
+/// and [`Ndjson`](super::Ndjson) (with the `json` feature),
+/// [`Csv`](super::csv::Csv) (with the `csv` feature), and results of these.
+///
+/// Any other [`IntoResponse`] type can be wrapped once converted with
+/// [`IntoResponse::into_response`], but the size of the converted body is
+/// only known if it has a `Content-Length` header.
+pub trait IntoCompressible {
+    /// The type of the response body.
+    type Body: http_body::Body<Data = Bytes, Error: Into<BoxError>> + 'static;
+
+    /// Converts `self` into an [`http::Response`](hyperium::Response).
+    fn into_compressible(self) -> HttpResult<hyperium::Response<Self::Body>>;
+}
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+impl<B> IntoCompressible for hyperium::Response<B>
+where
+    B: http_body::Body<Data = Bytes, Error: Into<BoxError>> + 'static,
+{
+    type Body = B;
+
+    fn into_compressible(self) -> HttpResult<hyperium::Response<B>> {
+        Ok(self)
+    }
+}
+
+impl<B> IntoCompressible for (hyperium::StatusCode, B)
+where
+    B: http_body::Body<Data = Bytes, Error: Into<BoxError>> + 'static,
+{
+    type Body = B;
+
+    fn into_compressible(self) -> HttpResult<hyperium::Response<B>> {
+        let mut response = hyperium::Response::new(self.1);
+        *response.status_mut() = self.0;
+        Ok(response)
+    }
+}
+
+impl IntoCompressible for types::Response {
+    type Body = IncomingBody<types::Response>;
+
+    fn into_compressible(self) -> HttpResult<Response> {
+        Response::from_response(self)
+    }
+}
+
+impl IntoCompressible for &'static str {
+    type Body = FullBody<Bytes>;
+
+    fn into_compressible(self) -> HttpResult<hyperium::Response<Self::Body>> {
+        Bytes::from_static(self.as_bytes()).into_compressible()
+    }
+}
+
+impl IntoCompressible for String {
+    type Body = FullBody<Bytes>;
+
+    fn into_compressible(self) -> HttpResult<hyperium::Response<Self::Body>> {
+        Bytes::from(self).into_compressible()
+    }
+}
+
+impl IntoCompressible for Vec<u8> {
+    type Body = FullBody<Bytes>;
+
+    fn into_compressible(self) -> HttpResult<hyperium::Response<Self::Body>> {
+        Bytes::from(self).into_compressible()
+    }
+}
+
+impl IntoCompressible for Bytes {
+    type Body = FullBody<Bytes>;
+
+    fn into_compressible(self) -> HttpResult<hyperium::Response<Self::Body>> {
+        Ok(hyperium::Response::new(FullBody::new(self)))
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T: serde::Serialize> IntoCompressible for super::Json<T> {
+    type Body = FullBody<Bytes>;
+
+    fn into_compressible(self) -> HttpResult<hyperium::Response<Self::Body>> {
+        super::json_response(hyperium::StatusCode::OK, &self.0)
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T: serde::Serialize> IntoCompressible for (hyperium::StatusCode, super::Json<T>) {
+    type Body = FullBody<Bytes>;
+
+    fn into_compressible(self) -> HttpResult<hyperium::Response<Self::Body>> {
+        super::json_response(self.0, &self.1.0)
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T, S> IntoCompressible for super::Ndjson<S>
+where
+    T: serde::Serialize + 'static,
+    S: futures::Stream<Item = T> + 'static,
+{
+    type Body = StreamingBody;
+
+    fn into_compressible(self) -> HttpResult<hyperium::Response<StreamingBody>> {
+        let mut response =
+            hyperium::Response::new(Box::pin(super::body::ndjson(self.0)) as StreamingBody);
+        response.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/x-ndjson"),
+        );
+        Ok(response)
+    }
+}
+
+#[cfg(feature = "csv")]
+impl<T, S> IntoCompressible for super::csv::Csv<S>
+where
+    T: serde::Serialize + 'static,
+    S: futures::Stream<Item = T> + 'static,
+{
+    type Body = StreamingBody;
+
+    fn into_compressible(self) -> HttpResult<hyperium::Response<StreamingBody>> {
+        let mut response = hyperium::Response::new(Box::pin(self.into_body()) as StreamingBody);
+        response.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("text/csv; charset=utf-8"),
+        );
+        Ok(response)
+    }
+}
+
+impl<T, E> IntoCompressible for Result<T, E>
+where
+    T: IntoCompressible,
+    E: Into<super::Error>,
+{
+    type Body = Either<T::Body, IncomingBody<types::Response>>;
+
+    fn into_compressible(self) -> HttpResult<hyperium::Response<Self::Body>> {
+        match self {
+            Ok(ok) => Ok(ok.into_compressible()?.map(Either::Left)),
+            Err(err) => {
+                let response = Err::<(), E>(err).into_response()?;
+                Ok(Response::from_response(response)?.map(Either::Right))
+            }
+        }
+    }
+}
+
+/// The body of a streamed [`Ndjson`](super::Ndjson) or
+/// [`Csv`](super::csv::Csv) response.
+#[cfg(any(feature = "json", feature = "csv"))]
+type StreamingBody = Pin<Box<dyn http_body::Body<Data = Bytes, Error = anyhow::Error>>>;
+
+/// Whether `response` should be compressed under `config`.
+///
+/// Responses without a body, responses that already have a
+/// `Content-Encoding`, already-compressed or skipped content types, and
+/// bodies known to be smaller than the configured minimum size are left as
+/// they are.
+fn should_compress<B: http_body::Body>(
+    response: &hyperium::Response<B>,
+    config: &CompressionConfig,
+) -> bool {
+    let status = response.status();
+    if status.is_informational()
+        || status == hyperium::StatusCode::NO_CONTENT
+        || status == hyperium::StatusCode::NOT_MODIFIED
+    {
+        return false;
+    }
+    let headers = response.headers();
+    if headers.contains_key(header::CONTENT_ENCODING) {
+        return false;
+    }
+    if let Some(content_type) = headers.get(header::CONTENT_TYPE)
+        && is_precompressed(content_type.to_str().unwrap_or_default())
+    {
+        return false;
+    }
+    if config.skips(response) {
+        return false;
+    }
+    let size = response.body().size_hint();
+    size.upper().is_none_or(|upper| upper >= config.min_size)
+}
+
+enum Encoder {
+    Gzip(flate2::write::GzEncoder<Vec<u8>>),
+    #[cfg(feature = "brotli")]
+    Brotli(Box<brotli::CompressorWriter<Vec<u8>>>),
+}
+
+impl Encoder {
+    fn new(encoding: Encoding, level: CompressionLevel) -> Self {
+        match encoding {
+            Encoding::Gzip => {
+                let level = match level {
+                    CompressionLevel::Fastest => flate2::Compression::fast(),
+                    CompressionLevel::Default => flate2::Compression::default(),
+                    CompressionLevel::Best => flate2::Compression::best(),
+                };
+                Encoder::Gzip(flate2::write::GzEncoder::new(Vec::new(), level))
+            }
+            #[cfg(feature = "brotli")]
+            Encoding::Brotli => {
+                let quality = match level {
+                    CompressionLevel::Fastest => 1,
+                    CompressionLevel::Default => 5,
+                    CompressionLevel::Best => 11,
+                };
+                Encoder::Brotli(Box::new(brotli::CompressorWriter::new(
+                    Vec::new(),
+                    4096,
+                    quality,
+                    22,
+                )))
+            }
+        }
+    }
+
+    /// Compresses `data`, returning whatever output is ready. If `flush` is
+    /// set, all of the input so far is flushed into the output, so that a
+    /// streamed response is not held back.
+    fn encode(&mut self, data: &[u8], flush: bool) -> std::io::Result<Vec<u8>> {
+        match self {
+            Encoder::Gzip(e) => {
+                e.write_all(data)?;
+                if flush {
+                    e.flush()?;
+                }
+                Ok(std::mem::take(e.get_mut()))
+            }
+            #[cfg(feature = "brotli")]
+            Encoder::Brotli(e) => {
+                e.write_all(data)?;
+                if flush {
+                    e.flush()?;
+                }
+                Ok(std::mem::take(e.get_mut()))
+            }
+        }
+    }
+
+    /// Completes the compressed stream, returning the remaining output.
+    fn finish(self) -> std::io::Result<Vec<u8>> {
+        match self {
+            Encoder::Gzip(e) => e.finish(),
+            #[cfg(feature = "brotli")]
+            Encoder::Brotli(e) => Ok(e.into_inner()),
+        }
+    }
+}
+
+/// A body that compresses the data frames of an inner body as they arrive.
+struct CompressedBody<B> {
+    inner: Pin<Box<B>>,
+    encoder: Option<Encoder>,
+    trailers: Option<HeaderMap>,
+    flush_threshold: usize,
+    /// Bytes of input written to the encoder since it was last flushed.
+    unflushed: usize,
+}
+
+impl<B> CompressedBody<B> {
+    fn new(inner: B, encoding: Encoding, config: &CompressionConfig) -> Self {
+        Self {
+            inner: Box::pin(inner),
+            encoder: Some(Encoder::new(encoding, config.level)),
+            trailers: None,
+            flush_threshold: config.flush_threshold,
+            unflushed: 0,
+        }
+    }
+}
+
+impl<B> http_body::Body for CompressedBody<B>
+where
+    B: http_body::Body<Data = Bytes, Error: Into<BoxError>>,
+{
+    type Data = Bytes;
+    type Error = BoxError;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<http_body::Frame<Bytes>, Self::Error>>> {
+        let this = &mut *self;
+        loop {
+            let Some(encoder) = this.encoder.as_mut() else {
+                return Poll::Ready(
+                    this.trailers
+                        .take()
+                        .map(|t| Ok(http_body::Frame::trailers(t))),
+                );
+            };
+            let frame = match ready!(this.inner.as_mut().poll_frame(cx)) {
+                Some(Ok(frame)) => frame,
+                Some(Err(e)) => return Poll::Ready(Some(Err(e.into()))),
+                None => {
+                    let rest = this
+                        .encoder
+                        .take()
+                        .unwrap()
+                        .finish()
+                        .map_err(encode_error)?;
+                    return Poll::Ready(Some(Ok(http_body::Frame::data(rest.into()))));
+                }
+            };
+            match frame.into_data() {
+                Ok(data) => {
+                    this.unflushed += data.len();
+                    let flush = this.unflushed >= this.flush_threshold;
+                    if flush {
+                        this.unflushed = 0;
+                    }
+                    let compressed = encoder.encode(&data, flush).map_err(encode_error)?;
+                    if !compressed.is_empty() {
+                        return Poll::Ready(Some(Ok(http_body::Frame::data(compressed.into()))));
+                    }
+                }
+                Err(frame) => {
+                    if let Ok(trailers) = frame.into_trailers() {
+                        this.trailers = Some(trailers);
+                    }
+                    let rest = this
+                        .encoder
+                        .take()
+                        .unwrap()
+                        .finish()
+                        .map_err(encode_error)?;
+                    return Poll::Ready(Some(Ok(http_body::Frame::data(rest.into()))));
+                }
+            }
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.encoder.is_none() && self.trailers.is_none()
+    }
+}
+
+/// Marks a strong `ETag` as weak, as the compressed body is no longer
+/// byte-for-byte identical to the one the tag was computed for.
+fn weaken_etag(headers: &mut HeaderMap) {
+    let Some(etag) = headers.get(header::ETAG) else {
+        return;
+    };
+    if etag.as_bytes().starts_with(b"W/") {
+        return;
+    }
+    let mut weak = b"W/".to_vec();
+    weak.extend_from_slice(etag.as_bytes());
+    match HeaderValue::from_bytes(&weak) {
+        Ok(weak) => headers.insert(header::ETAG, weak),
+        Err(_) => headers.remove(header::ETAG),
+    };
+}
+
+fn encode_error(e: std::io::Error) -> types::ErrorCode {
+    types::ErrorCode::internal(format_args!("response compression failed: {e}"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn accept(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT_ENCODING, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn negotiation() {
+        assert_eq!(None, Encoding::negotiate(&HeaderMap::new()));
+        assert_eq!(None, Encoding::negotiate(&accept("identity")));
+        assert_eq!(None, Encoding::negotiate(&accept("gzip;q=0, br;q=0")));
+        assert_eq!(None, Encoding::negotiate(&accept("*;q=0")));
+        assert_eq!(
+            Some(Encoding::Gzip),
+            Encoding::negotiate(&accept("deflate, GZIP"))
+        );
+        assert_eq!(
+            Some(Encoding::Gzip),
+            Encoding::negotiate(&accept("gzip;q=0.9, br;q=0.1"))
+        );
+        #[cfg(feature = "brotli")]
+        {
+            assert_eq!(
+                Some(Encoding::Brotli),
+                Encoding::negotiate(&accept("gzip, br"))
+            );
+            assert_eq!(Some(Encoding::Brotli), Encoding::negotiate(&accept("*")));
+        }
+    }
+
+    #[test]
+    fn configured_compression() {
+        let config = CompressionConfig::default().encodings([Encoding::Gzip]);
+        assert_eq!(Some(Encoding::Gzip), config.negotiate(&accept("br, gzip")));
+        assert_eq!(None, config.negotiate(&accept("br")));
+        let config = CompressionConfig::default().encodings([]);
+        assert_eq!(None, config.negotiate(&accept("gzip")));
+
+        let response = |content_type, len: usize| {
+            hyperium::Response::builder()
+                .header(header::CONTENT_TYPE, content_type)
+                .body(http_body_util::Full::new(Bytes::from(vec![b'a'; len])))
+                .unwrap()
+        };
+        let config = CompressionConfig::default()
+            .min_size(10)
+            .skip_content_type("text/event-stream");
+        assert!(should_compress(&response("text/plain", 10), &config));
+        assert!(!should_compress(&response("text/plain", 9), &config));
+        assert!(!should_compress(
+            &response("Text/Event-Stream", 100),
+            &config
+        ));
+        assert!(!should_compress(&response("image/png", 100), &config));
+        assert!(!should_compress(
+            &response("text/plain", 100),
+            &CompressionConfig::default()
+        ));
+
+        let compress = |level| {
+            let mut encoder = Encoder::new(Encoding::Gzip, level);
+            let mut out = encoder
+                .encode(&b"compressible ".repeat(500), false)
+                .unwrap();
+            out.extend(encoder.finish().unwrap());
+            out.len()
+        };
+        assert!(compress(CompressionLevel::Best) <= compress(CompressionLevel::Fastest));
+    }
+
+    #[test]
+    fn compressed_responses() {
+        use std::io::Read;
+
+        fn body<B: http_body::Body<Error: std::fmt::Debug>>(
+            response: hyperium::Response<B>,
+        ) -> Bytes {
+            use http_body_util::BodyExt;
+            let collected = futures::executor::block_on(response.into_body().collect());
+            collected.unwrap().to_bytes()
+        }
+        let gzip = accept("gzip");
+
+        let response = Compressed::new(&gzip, "too short").into_http().unwrap();
+        assert!(!response.headers().contains_key(header::CONTENT_ENCODING));
+        assert_eq!("too short", body(response));
+
+        let response = Compressed::new(&gzip, super::super::Json([1, 2, 3]))
+            .into_http()
+            .unwrap();
+        assert!(!response.headers().contains_key(header::CONTENT_ENCODING));
+
+        let text = "compressible ".repeat(200);
+        let response = hyperium::Response::builder()
+            .header(header::ETAG, "\"v1\"")
+            .header(header::CONTENT_LENGTH, text.len())
+            .body(FullBody::new(Bytes::from(text.clone())))
+            .unwrap();
+        let response = Compressed::new(&gzip, response).into_http().unwrap();
+        let headers = response.headers();
+        assert_eq!("gzip", headers[header::CONTENT_ENCODING]);
+        assert_eq!("accept-encoding", headers[header::VARY]);
+        assert_eq!("W/\"v1\"", headers[header::ETAG]);
+        assert!(!headers.contains_key(header::CONTENT_LENGTH));
+        let mut decoded = String::new();
+        flate2::read::GzDecoder::new(&body(response)[..])
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(text, decoded);
+
+        let response = Compressed::new(&HeaderMap::new(), text)
+            .into_http()
+            .unwrap();
+        assert!(!response.headers().contains_key(header::CONTENT_ENCODING));
+    }
+
+    #[test]
+    fn flushing() {
+        use http_body_util::BodyExt;
+        use std::io::Read;
+
+        let frames = |config: CompressionConfig| {
+            let chunks = ["first, ", "second, ", "third"]
+                .map(|s| Ok::<_, std::convert::Infallible>(http_body::Frame::data(Bytes::from(s))));
+            let inner = http_body_util::StreamBody::new(futures::stream::iter(chunks));
+            let mut body = CompressedBody::new(inner, Encoding::Gzip, &config);
+            let mut frames = Vec::new();
+            while let Some(frame) = futures::executor::block_on(body.frame()) {
+                frames.push(frame.unwrap().into_data().unwrap());
+            }
+            frames
+        };
+
+        let every_chunk = frames(CompressionConfig::default().flush_threshold(0));
+        let at_end = frames(CompressionConfig::default());
+        assert!(at_end.len() < every_chunk.len());
+        for frames in [every_chunk, at_end] {
+            let mut decoded = String::new();
+            flate2::read::GzDecoder::new(&frames.concat()[..])
+                .read_to_string(&mut decoded)
+                .unwrap();
+            assert_eq!("first, second, third", decoded);
+        }
+    }
+
+    #[test]
+    fn precompressed_types() {
+        assert!(is_precompressed("image/png"));
+        assert!(is_precompressed("application/zip"));
+        assert!(!is_precompressed("image/svg+xml"));
+        assert!(!is_precompressed("text/html; charset=utf-8"));
+        assert!(!is_precompressed("application/json"));
+    }
+}
@@ -0,0 +1,184 @@
+//! Streaming CSV responses.
+//!
+//! [`Csv`] turns a [`Stream`](futures::Stream) of rows into a `text/csv`
+//! response. A row is anything that serializes to a flat record, such as a
+//! `Vec<String>`, a tuple, or a struct with scalar fields. Fields containing
+//! the delimiter, quotes or line breaks are quoted as described in
+//! [RFC 4180](https://www.rfc-editor.org/rfc/rfc4180), and records end with
+//! `\r\n`.
+//!
+//! Each row is sent as soon as the stream yields it, so large exports do not
+//! have to be collected into memory before responding.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use spin_sdk::http::{IntoResponse, Request, csv::Csv};
+//! use spin_sdk::http_service;
+//!
+//! #[derive(serde::Serialize)]
+//! struct Sale { region: String, total: f64 }
+//!
+//! #[http_service]
+//! async fn handler(_req: Request) -> impl IntoResponse {
+//!     let (tx, sales) = futures::channel::mpsc::channel::<Sale>(16);
+//!     spin_sdk::wasip3::spawn(produce_sales(tx));
+//!     Csv::new(sales).with_field_names()
+//! }
+//! ```
+
+use super::{HttpResult, IntoResponse, body, types};
+use ::csv::{Terminator, WriterBuilder};
+use bytes::Bytes;
+use futures::StreamExt;
+use hyperium::{HeaderValue, header};
+
+/// A CSV response streamed from a [`Stream`](futures::Stream) of rows.
+///
+/// By default no header row is written. Use [`with_header`](Self::with_header)
+/// to name the columns, or [`with_field_names`](Self::with_field_names) to
+/// take the names from the fields of a struct row.
+pub struct Csv<S> {
+    rows: S,
+    header: Header,
+}
+
+enum Header {
+    None,
+    FieldNames,
+    Names(Vec<String>),
+}
+
+impl<S> Csv<S> {
+    /// Creates a CSV response from a stream of rows, without a header row.
+    pub fn new(rows: S) -> Self {
+        Self {
+            rows,
+            header: Header::None,
+        }
+    }
+
+    /// Writes `names` as the first row.
+    pub fn with_header<I>(mut self, names: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.header = Header::Names(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Writes the field names of the first row as the header row.
+    ///
+    /// Names are only taken from structs with scalar fields, so for other
+    /// rows, or if the stream is empty, no header is written.
+    pub fn with_field_names(mut self) -> Self {
+        self.header = Header::FieldNames;
+        self
+    }
+
+    /// Converts the rows into a streaming body, without setting any headers.
+    ///
+    /// If a row cannot be serialized as a CSV record, the body ends with an
+    /// error.
+    pub fn into_body<T>(self) -> impl http_body::Body<Data = Bytes, Error = anyhow::Error>
+    where
+        T: serde::Serialize,
+        S: futures::Stream<Item = T>,
+    {
+        let (header, mut field_names) = match self.header {
+            Header::None => (None, false),
+            Header::FieldNames => (None, true),
+            Header::Names(names) => (Some(encode(&names, false)), false),
+        };
+        let rows = self
+            .rows
+            .map(move |row| encode(&row, std::mem::take(&mut field_names)));
+        body::from_stream(futures::stream::iter(header).chain(rows))
+    }
+}
+
+impl<T, S> IntoResponse for Csv<S>
+where
+    T: serde::Serialize + 'static,
+    S: futures::Stream<Item = T> + 'static,
+{
+    fn into_response(self) -> HttpResult<types::Response> {
+        let mut resp = hyperium::Response::new(self.into_body());
+        resp.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("text/csv; charset=utf-8"),
+        );
+        resp.into_response()
+    }
+}
+
+/// Serializes one record, preceded by a header row of its field names if
+/// `field_names` is set.
+fn encode<T: serde::Serialize>(record: &T, field_names: bool) -> Result<Bytes, ::csv::Error> {
+    let mut writer = WriterBuilder::new()
+        .has_headers(field_names)
+        .terminator(Terminator::CRLF)
+        .from_writer(Vec::new());
+    writer.serialize(record)?;
+    let data = writer
+        .into_inner()
+        .map_err(|e| ::csv::Error::from(e.into_error()))?;
+    Ok(Bytes::from(data))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use http_body_util::BodyExt;
+    use serde::ser::SerializeStruct;
+
+    struct Sale {
+        region: &'static str,
+        total: f64,
+    }
+
+    impl serde::Serialize for Sale {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut s = serializer.serialize_struct("Sale", 2)?;
+            s.serialize_field("region", self.region)?;
+            s.serialize_field("total", &self.total)?;
+            s.end()
+        }
+    }
+
+    fn collect(body: impl http_body::Body<Data = Bytes, Error = anyhow::Error>) -> String {
+        let body = futures::executor::block_on(body.collect()).unwrap();
+        String::from_utf8(body.to_bytes().to_vec()).unwrap()
+    }
+
+    #[test]
+    fn csv_rows() {
+        let rows = futures::stream::iter([vec!["plain", "a,b"], vec!["say \"hi\"", "two\nlines"]]);
+        assert_eq!(
+            "name,note\r\nplain,\"a,b\"\r\n\"say \"\"hi\"\"\",\"two\nlines\"\r\n",
+            collect(Csv::new(rows).with_header(["name", "note"]).into_body())
+        );
+
+        let sales = futures::stream::iter([
+            Sale {
+                region: "north",
+                total: 1.5,
+            },
+            Sale {
+                region: "south",
+                total: 2.0,
+            },
+        ]);
+        assert_eq!(
+            "region,total\r\nnorth,1.5\r\nsouth,2.0\r\n",
+            collect(Csv::new(sales).with_field_names().into_body())
+        );
+
+        let rows = futures::stream::iter([vec!["a"]]);
+        assert_eq!(
+            "a\r\n",
+            collect(Csv::new(rows).with_field_names().into_body())
+        );
+    }
+}
@@ -0,0 +1,116 @@
+//! Constructors for common [`ErrorCode`] values.
+
+use std::{fmt, io};
+use wasip3::http::types::ErrorCode;
+
+/// Extension trait with shorthand constructors for [`ErrorCode`].
+///
+/// # Examples
+///
+/// ```ignore
+/// use spin_sdk::http::ErrorCodeExt;
+/// use spin_sdk::wasip3::http::types::ErrorCode;
+///
+/// let config = load_config().map_err(ErrorCode::internal)?;
+/// let file = std::fs::read("data.bin").map_err(|e| ErrorCode::from_io_error(&e))?;
+/// ```
+pub trait ErrorCodeExt: Sized {
+    /// An [`ErrorCode::InternalError`] carrying `message`.
+    fn internal(message: impl fmt::Display) -> Self;
+
+    /// An [`ErrorCode::HttpRequestUriInvalid`].
+    fn invalid_uri() -> Self;
+
+    /// An [`ErrorCode::HttpRequestMethodInvalid`].
+    fn invalid_method() -> Self;
+
+    /// An [`ErrorCode::HttpResponseTimeout`], for when the response did not
+    /// arrive in time.
+    fn timeout() -> Self;
+
+    /// An [`ErrorCode::HttpRequestBodySize`] with no size recorded.
+    fn request_body_too_large() -> Self;
+
+    /// An [`ErrorCode::HttpResponseBodySize`] with no size recorded.
+    fn response_body_too_large() -> Self;
+
+    /// Maps an I/O error to the closest error code.
+    ///
+    /// Connection failures and timeouts map to the matching connection
+    /// codes, an I/O error that wraps an [`ErrorCode`] yields that code, and
+    /// anything else becomes [`ErrorCode::InternalError`] with the error's
+    /// message.
+    fn from_io_error(err: &io::Error) -> Self;
+}
+
+impl ErrorCodeExt for ErrorCode {
+    fn internal(message: impl fmt::Display) -> Self {
+        ErrorCode::InternalError(Some(message.to_string()))
+    }
+
+    fn invalid_uri() -> Self {
+        ErrorCode::HttpRequestUriInvalid
+    }
+
+    fn invalid_method() -> Self {
+        ErrorCode::HttpRequestMethodInvalid
+    }
+
+    fn timeout() -> Self {
+        ErrorCode::HttpResponseTimeout
+    }
+
+    fn request_body_too_large() -> Self {
+        ErrorCode::HttpRequestBodySize(None)
+    }
+
+    fn response_body_too_large() -> Self {
+        ErrorCode::HttpResponseBodySize(None)
+    }
+
+    fn from_io_error(err: &io::Error) -> Self {
+        if let Some(code) = err.get_ref().and_then(|e| e.downcast_ref::<ErrorCode>()) {
+            return code.clone();
+        }
+        match err.kind() {
+            io::ErrorKind::TimedOut => ErrorCode::ConnectionTimeout,
+            io::ErrorKind::ConnectionRefused => ErrorCode::ConnectionRefused,
+            io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::BrokenPipe => ErrorCode::ConnectionTerminated,
+            io::ErrorKind::AddrNotAvailable => ErrorCode::DestinationUnavailable,
+            io::ErrorKind::UnexpectedEof => ErrorCode::HttpResponseIncomplete,
+            _ => ErrorCode::internal(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn io_errors() {
+        let code = |kind| ErrorCode::from_io_error(&io::Error::from(kind));
+        assert!(matches!(
+            code(io::ErrorKind::TimedOut),
+            ErrorCode::ConnectionTimeout
+        ));
+        assert!(matches!(
+            code(io::ErrorKind::BrokenPipe),
+            ErrorCode::ConnectionTerminated
+        ));
+
+        let wrapped = io::Error::other(ErrorCode::HttpRequestDenied);
+        assert!(matches!(
+            ErrorCode::from_io_error(&wrapped),
+            ErrorCode::HttpRequestDenied
+        ));
+
+        let other = io::Error::other("disk full");
+        assert!(matches!(
+            ErrorCode::from_io_error(&other),
+            ErrorCode::InternalError(Some(msg)) if msg == "disk full"
+        ));
+    }
+}
@@ -0,0 +1,315 @@
+//! Convenience methods for reading header values.
+
+use hyperium::{HeaderMap, HeaderValue, header, header::AsHeaderName};
+use std::{fmt, str::FromStr};
+
+/// Extension trait for reading header values without converting them by hand.
+///
+/// This is implemented for [`HeaderMap`] and for [`http::Request`](hyperium::Request)
+/// and [`http::Response`](hyperium::Response) with any body type, so it
+/// works for incoming requests as well as responses from outbound requests.
+///
+/// # Examples
+///
+/// ```ignore
+/// use spin_sdk::http::HeaderExt;
+///
+/// let response = spin_sdk::http::get("https://example.com").await?;
+/// let content_type = response.header_str("content-type");
+/// let content_length: Option<u64> = response.header_parsed("content-length");
+/// let cookies = response.header_all("set-cookie");
+/// ```
+pub trait HeaderExt {
+    /// Returns the first value of the header as a string.
+    ///
+    /// Returns `None` if the header is missing or its value is not visible
+    /// ASCII.
+    fn header_str<K: AsHeaderName>(&self, name: K) -> Option<&str>;
+
+    /// Parses the first value of the header, after trimming surrounding
+    /// whitespace.
+    ///
+    /// Returns `None` if the header is missing or cannot be parsed as `T`.
+    fn header_parsed<T: FromStr, K: AsHeaderName>(&self, name: K) -> Option<T> {
+        self.header_str(name)?.trim().parse().ok()
+    }
+
+    /// Returns every value of the header, in the order they were received.
+    ///
+    /// Use this for headers that may legitimately be repeated, such as
+    /// `Forwarded` or `Set-Cookie`, where reading only the first value would
+    /// silently drop the rest. Values combined into one comma-separated
+    /// header line are returned as a single value.
+    fn header_all<K: AsHeaderName>(&self, name: K) -> Vec<&HeaderValue>;
+
+    /// Parses the `Content-Type` header.
+    ///
+    /// Returns `None` if the header is missing or is not a valid media type.
+    fn content_type(&self) -> Option<MediaType> {
+        self.header_str(header::CONTENT_TYPE)?.parse().ok()
+    }
+}
+
+impl HeaderExt for HeaderMap {
+    fn header_str<K: AsHeaderName>(&self, name: K) -> Option<&str> {
+        self.get(name)?.to_str().ok()
+    }
+
+    fn header_all<K: AsHeaderName>(&self, name: K) -> Vec<&HeaderValue> {
+        self.get_all(name).iter().collect()
+    }
+}
+
+impl<B> HeaderExt for hyperium::Request<B> {
+    fn header_str<K: AsHeaderName>(&self, name: K) -> Option<&str> {
+        self.headers().header_str(name)
+    }
+
+    fn header_all<K: AsHeaderName>(&self, name: K) -> Vec<&HeaderValue> {
+        self.headers().header_all(name)
+    }
+}
+
+impl<B> HeaderExt for hyperium::Response<B> {
+    fn header_str<K: AsHeaderName>(&self, name: K) -> Option<&str> {
+        self.headers().header_str(name)
+    }
+
+    fn header_all<K: AsHeaderName>(&self, name: K) -> Vec<&HeaderValue> {
+        self.headers().header_all(name)
+    }
+}
+
+/// A parsed media type, such as the value of a `Content-Type` header.
+///
+/// The type, subtype and parameter names are compared without regard to
+/// case, and whitespace around the parts is ignored, so
+/// `Application/JSON ; charset="utf-8"` has the essence `application/json`
+/// and the charset `utf-8`.
+///
+/// # Examples
+///
+/// ```ignore
+/// use spin_sdk::http::{HeaderExt, MediaType};
+///
+/// let content_type = request.content_type();
+/// if content_type.as_ref().is_some_and(|ct| ct.matches("application/json")) {
+///     // ...
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MediaType {
+    essence: String,
+    slash: usize,
+    params: Vec<(String, String)>,
+}
+
+/// The error returned when a string is not a valid [`MediaType`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidMediaType;
+
+impl fmt::Display for InvalidMediaType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid media type")
+    }
+}
+
+impl std::error::Error for InvalidMediaType {}
+
+impl MediaType {
+    /// The type and subtype, in lower case and without parameters, such as
+    /// `text/html`.
+    pub fn essence(&self) -> &str {
+        &self.essence
+    }
+
+    /// The top-level type, such as `text`.
+    pub fn type_(&self) -> &str {
+        &self.essence[..self.slash]
+    }
+
+    /// The subtype, such as `html`.
+    pub fn subtype(&self) -> &str {
+        &self.essence[self.slash + 1..]
+    }
+
+    /// The value of the named parameter, with any quoting removed.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// The value of the `charset` parameter.
+    pub fn charset(&self) -> Option<&str> {
+        self.param("charset")
+    }
+
+    /// Whether this media type matches `pattern`, ignoring parameters and
+    /// case.
+    ///
+    /// The pattern is either a full media type such as `application/json`,
+    /// a type followed by `/` or `/*` (such as `text/*`) to accept any
+    /// subtype, or `*/*` to accept anything.
+    pub fn matches(&self, pattern: &str) -> bool {
+        let pattern = pattern.trim();
+        if pattern == "*/*" {
+            return true;
+        }
+        match pattern
+            .strip_suffix('*')
+            .unwrap_or(pattern)
+            .strip_suffix('/')
+        {
+            Some(ty) => self.type_().eq_ignore_ascii_case(ty),
+            None => self.essence.eq_ignore_ascii_case(pattern),
+        }
+    }
+}
+
+impl FromStr for MediaType {
+    type Err = InvalidMediaType;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (essence, mut rest) = s.split_once(';').unwrap_or((s, ""));
+        let essence = essence.trim().to_ascii_lowercase();
+        let slash = essence.find('/').ok_or(InvalidMediaType)?;
+        let (ty, subtype) = (&essence[..slash], &essence[slash + 1..]);
+        if !is_token(ty) || !is_token(subtype) {
+            return Err(InvalidMediaType);
+        }
+
+        let mut params = Vec::new();
+        loop {
+            rest = rest.trim_start_matches(|c: char| c == ';' || c.is_whitespace());
+            if rest.is_empty() {
+                break;
+            }
+            let (name, after) = rest.split_once('=').ok_or(InvalidMediaType)?;
+            let name = name.trim();
+            if !is_token(name) {
+                return Err(InvalidMediaType);
+            }
+            let after = after.trim_start();
+            let (value, remainder) = match after.strip_prefix('"') {
+                Some(quoted) => parse_quoted(quoted)?,
+                None => {
+                    let end = after.find(';').unwrap_or(after.len());
+                    (after[..end].trim_end().to_owned(), &after[end..])
+                }
+            };
+            params.push((name.to_ascii_lowercase(), value));
+            rest = remainder;
+        }
+
+        Ok(Self {
+            essence,
+            slash,
+            params,
+        })
+    }
+}
+
+impl fmt::Display for MediaType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.essence)?;
+        for (name, value) in &self.params {
+            if is_token(value) {
+                write!(f, "; {name}={value}")?;
+            } else {
+                let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+                write!(f, "; {name}=\"{escaped}\"")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether `s` is a non-empty HTTP token.
+fn is_token(s: &str) -> bool {
+    !s.is_empty()
+        && s.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+/// Parses the rest of a quoted string whose opening quote has been removed,
+/// returning the unescaped value and the text after the closing quote.
+fn parse_quoted(s: &str) -> Result<(String, &str), InvalidMediaType> {
+    let mut value = String::new();
+    let mut chars = s.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => value.push(chars.next().ok_or(InvalidMediaType)?.1),
+            '"' => return Ok((value, &s[i + 1..])),
+            c => value.push(c),
+        }
+    }
+    Err(InvalidMediaType)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn read_headers() {
+        let request = hyperium::Request::builder()
+            .header("content-length", " 42 ")
+            .header("x-binary", &b"\xff"[..])
+            .header("forwarded", "for=192.0.2.1")
+            .header("forwarded", "for=198.51.100.7")
+            .body(())
+            .unwrap();
+
+        assert_eq!(Some(" 42 "), request.header_str("content-length"));
+        assert_eq!(Some(42u64), request.header_parsed("Content-Length"));
+        assert_eq!(None, request.header_parsed::<u64, _>("x-missing"));
+        assert_eq!(None, request.header_str("x-binary"));
+        assert_eq!(
+            None,
+            request.headers().header_parsed::<bool, _>("content-length")
+        );
+        assert_eq!(
+            vec!["for=192.0.2.1", "for=198.51.100.7"],
+            request.header_all("Forwarded")
+        );
+        assert!(request.header_all("x-missing").is_empty());
+    }
+
+    #[test]
+    fn media_types() {
+        let media: MediaType = "Application/JSON ; Charset=\"utf-8\"; q=\"a\\\"b;c\""
+            .parse()
+            .unwrap();
+        assert_eq!("application/json", media.essence());
+        assert_eq!("application", media.type_());
+        assert_eq!("json", media.subtype());
+        assert_eq!(Some("utf-8"), media.charset());
+        assert_eq!(Some("a\"b;c"), media.param("Q"));
+        assert_eq!(
+            "application/json; charset=utf-8; q=\"a\\\"b;c\"",
+            media.to_string()
+        );
+
+        assert!(media.matches("application/json"));
+        assert!(media.matches("APPLICATION/*"));
+        assert!(media.matches("application/"));
+        assert!(media.matches("*/*"));
+        assert!(!media.matches("application/json-patch+json"));
+        assert!(!media.matches("text/*"));
+
+        assert!("json".parse::<MediaType>().is_err());
+        assert!("text/".parse::<MediaType>().is_err());
+        assert!("text/plain; charset".parse::<MediaType>().is_err());
+        assert!("text/plain; a=\"unterminated".parse::<MediaType>().is_err());
+
+        let response = hyperium::Response::builder()
+            .header("content-type", "text/html;charset=ISO-8859-1")
+            .body(())
+            .unwrap();
+        let content_type = response.content_type().unwrap();
+        assert!(content_type.matches("text/html"));
+        assert_eq!(Some("ISO-8859-1"), content_type.charset());
+    }
+}
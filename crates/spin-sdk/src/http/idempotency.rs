@@ -0,0 +1,304 @@
+//! Support for the `Idempotency-Key` request header.
+//!
+//! Clients of endpoints such as payments or order creation send an
+//! `Idempotency-Key` header so that a request retried after a network
+//! failure is not processed twice. [`idempotent`] runs a handler once per
+//! key, records its response in a key-value [`Store`], and replays that
+//! response for repeated requests with the same key.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use spin_sdk::http::{IntoResponse, Request, idempotency};
+//! use spin_sdk::http_service;
+//! use spin_sdk::key_value::Store;
+//! use std::time::Duration;
+//!
+//! #[http_service]
+//! async fn handler(request: Request) -> anyhow::Result<impl IntoResponse> {
+//!     let store = Store::open_default().await?;
+//!     let ttl = Duration::from_secs(24 * 60 * 60);
+//!     Ok(idempotency::idempotent(&store, ttl, request, create_order).await)
+//! }
+//!
+//! async fn create_order(request: Request) -> impl IntoResponse {
+//!     // Your logic goes here
+//! }
+//! ```
+
+use super::{Error, FromResponse, HeaderExt, IntoResponse, Request, Response};
+use crate::key_value::Store;
+use bytes::{Bytes, BytesMut};
+use http_body::{Body, Frame, SizeHint};
+use http_body_util::BodyExt;
+use hyperium::{HeaderName, HeaderValue, StatusCode};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime};
+use wasip3::http::types::ErrorCode;
+use wasip3::http_compat::IncomingResponseBody;
+
+/// The request header carrying the client's idempotency key.
+pub const IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
+
+/// The response header set to `true` on a replayed response.
+pub const IDEMPOTENT_REPLAYED: HeaderName = HeaderName::from_static("idempotent-replayed");
+
+/// The largest response body, in bytes, that [`idempotent`] records.
+///
+/// A larger response is returned to the client as usual, but is not recorded,
+/// so a retry of the request runs the handler again.
+pub const MAX_RECORDED_SIZE: usize = 1024 * 1024;
+
+/// A response produced by [`idempotent`].
+pub type IdempotentResponse = hyperium::Response<IdempotentBody>;
+
+/// The body of an [`IdempotentResponse`].
+///
+/// A recorded or replayed body is held in memory. Any other body streams
+/// from the handler's response, after whatever part of it was read while
+/// deciding whether to record it.
+pub struct IdempotentBody {
+    buffered: Bytes,
+    rest: Option<IncomingResponseBody>,
+}
+
+impl IdempotentBody {
+    fn full(data: Bytes) -> Self {
+        Self {
+            buffered: data,
+            rest: None,
+        }
+    }
+
+    fn streaming(body: IncomingResponseBody) -> Self {
+        Self {
+            buffered: Bytes::new(),
+            rest: Some(body),
+        }
+    }
+
+    /// Returns the whole body, if it was read into memory.
+    fn recorded(&self) -> Option<&Bytes> {
+        self.rest.is_none().then_some(&self.buffered)
+    }
+}
+
+impl Body for IdempotentBody {
+    type Data = Bytes;
+    type Error = ErrorCode;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, ErrorCode>>> {
+        if !self.buffered.is_empty() {
+            let data = std::mem::take(&mut self.buffered);
+            return Poll::Ready(Some(Ok(Frame::data(data))));
+        }
+        match &mut self.rest {
+            Some(rest) => Pin::new(rest).poll_frame(cx),
+            None => Poll::Ready(None),
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.buffered.is_empty() && self.rest.as_ref().is_none_or(Body::is_end_stream)
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        match &self.rest {
+            None => SizeHint::with_exact(self.buffered.len() as u64),
+            Some(rest) if self.buffered.is_empty() => rest.size_hint(),
+            // Part of `rest` has been read, so its own hint no longer applies.
+            Some(_) => SizeHint::default(),
+        }
+    }
+}
+
+/// Runs `handler` for `request`, unless a response for the same
+/// `Idempotency-Key` was recorded within the last `ttl`, in which case that
+/// response is returned instead with an `Idempotent-Replayed: true` header.
+///
+/// Keys are scoped to the method and path of the request, so the same key
+/// sent to two endpoints is handled separately. Requests without the header
+/// are passed to `handler` as they are, and its response is streamed back
+/// without being read into memory.
+///
+/// Otherwise the response body is buffered in memory so that it can be
+/// recorded, up to [`MAX_RECORDED_SIZE`] bytes; a larger response is
+/// streamed back without being recorded. Server errors (5xx) are not
+/// recorded either, so that a request which failed because of a transient
+/// problem can be retried. Expired entries are replaced when their key is
+/// next used.
+///
+/// Recording is best effort: the handler has already run by the time its
+/// response is stored, so if the store fails the error is logged to stderr
+/// and the response is returned anyway.
+///
+/// The store has no atomic insert, so two requests with the same key that
+/// arrive at the same moment may both be processed. This guards against
+/// retries, not against concurrent duplicates.
+pub async fn idempotent<F, Fut, R>(
+    store: &Store,
+    ttl: Duration,
+    request: Request,
+    handler: F,
+) -> Result<IdempotentResponse, Error>
+where
+    F: FnOnce(Request) -> Fut,
+    Fut: Future<Output = R>,
+    R: IntoResponse,
+{
+    let Some(key) = request
+        .header_str(IDEMPOTENCY_KEY)
+        .map(|key| storage_key(request.method().as_str(), request.uri().path(), key))
+    else {
+        return Ok(run(request, handler).await?.map(IdempotentBody::streaming));
+    };
+
+    let now = unix_millis(SystemTime::now());
+    let stored = store.get(&key).await.map_err(store_error)?;
+    if let Some((expires, mut response)) = stored.as_deref().and_then(decode)
+        && expires > now
+    {
+        response
+            .headers_mut()
+            .insert(IDEMPOTENT_REPLAYED, HeaderValue::from_static("true"));
+        return Ok(response);
+    }
+
+    let response = run(request, handler).await?;
+    let too_large = response.body().size_hint().lower() > MAX_RECORDED_SIZE as u64;
+    if response.status().is_server_error() || too_large {
+        return Ok(response.map(IdempotentBody::streaming));
+    }
+
+    let (parts, body) = response.into_parts();
+    let response = hyperium::Response::from_parts(parts, buffer(body).await?);
+    if let Some(body) = response.body().recorded() {
+        let expires = now.saturating_add(ttl.as_millis().try_into().unwrap_or(u64::MAX));
+        if let Err(e) = store.set(&key, encode(expires, &response, body)).await {
+            eprintln!("failed to record idempotent response: {e}");
+        }
+    }
+    Ok(response)
+}
+
+async fn run<F, Fut, R>(request: Request, handler: F) -> Result<Response, Error>
+where
+    F: FnOnce(Request) -> Fut,
+    Fut: Future<Output = R>,
+    R: IntoResponse,
+{
+    let response = handler(request).await.into_response()?;
+    Ok(Response::from_response(response)?)
+}
+
+/// Reads `body` into memory, unless it turns out to be larger than
+/// [`MAX_RECORDED_SIZE`], in which case the part read so far is kept in
+/// front of the rest of the stream.
+async fn buffer(mut body: IncomingResponseBody) -> Result<IdempotentBody, ErrorCode> {
+    let mut buf = BytesMut::new();
+    while let Some(frame) = body.frame().await {
+        if let Ok(data) = frame?.into_data() {
+            buf.extend_from_slice(&data);
+            if buf.len() > MAX_RECORDED_SIZE {
+                return Ok(IdempotentBody {
+                    buffered: buf.freeze(),
+                    rest: Some(body),
+                });
+            }
+        }
+    }
+    Ok(IdempotentBody::full(buf.freeze()))
+}
+
+fn store_error(e: crate::key_value::Error) -> Error {
+    anyhow::Error::new(e)
+        .context("idempotency store failed")
+        .into()
+}
+
+fn storage_key(method: &str, path: &str, key: &str) -> String {
+    format!("idempotency:{method} {path}:{key}")
+}
+
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis().try_into().unwrap_or(u64::MAX))
+}
+
+/// Serializes a recorded response: the expiry time, the status, the
+/// headers as length-prefixed names and values, and then the body.
+fn encode<B>(expires: u64, response: &hyperium::Response<B>, body: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(body.len() + 64);
+    out.extend_from_slice(&expires.to_be_bytes());
+    out.extend_from_slice(&response.status().as_u16().to_be_bytes());
+    out.extend_from_slice(&(response.headers().len() as u32).to_be_bytes());
+    for (name, value) in response.headers() {
+        for part in [name.as_str().as_bytes(), value.as_bytes()] {
+            out.extend_from_slice(&(part.len() as u32).to_be_bytes());
+            out.extend_from_slice(part);
+        }
+    }
+    out.extend_from_slice(body);
+    out
+}
+
+/// Parses a response recorded by [`encode`], returning `None` if it is
+/// malformed.
+fn decode(mut data: &[u8]) -> Option<(u64, IdempotentResponse)> {
+    fn take<'a>(data: &mut &'a [u8], n: usize) -> Option<&'a [u8]> {
+        let (head, tail) = data.split_at_checked(n)?;
+        *data = tail;
+        Some(head)
+    }
+    fn take_u32(data: &mut &[u8]) -> Option<u32> {
+        Some(u32::from_be_bytes(take(data, 4)?.try_into().ok()?))
+    }
+
+    let expires = u64::from_be_bytes(take(&mut data, 8)?.try_into().ok()?);
+    let status = u16::from_be_bytes(take(&mut data, 2)?.try_into().ok()?);
+    let mut response = hyperium::Response::new(IdempotentBody::full(Bytes::new()));
+    *response.status_mut() = StatusCode::from_u16(status).ok()?;
+    for _ in 0..take_u32(&mut data)? {
+        let len = take_u32(&mut data)? as usize;
+        let name = HeaderName::from_bytes(take(&mut data, len)?).ok()?;
+        let len = take_u32(&mut data)? as usize;
+        let value = HeaderValue::from_bytes(take(&mut data, len)?).ok()?;
+        response.headers_mut().append(name, value);
+    }
+    *response.body_mut() = IdempotentBody::full(Bytes::copy_from_slice(data));
+    Some((expires, response))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn recorded_responses() {
+        let response = hyperium::Response::builder()
+            .status(StatusCode::CREATED)
+            .header("location", "/orders/42")
+            .header("set-cookie", "a=1")
+            .header("set-cookie", "b=2")
+            .body(())
+            .unwrap();
+        let encoded = encode(1234, &response, b"{\"id\":42}");
+
+        let (expires, decoded) = decode(&encoded).unwrap();
+        assert_eq!(1234, expires);
+        assert_eq!(StatusCode::CREATED, decoded.status());
+        assert_eq!(response.headers(), decoded.headers());
+        let body = futures::executor::block_on(decoded.into_body().collect()).unwrap();
+        assert_eq!("{\"id\":42}", body.to_bytes());
+
+        assert!(decode(&encoded[..12]).is_none());
+        assert_eq!(
+            "idempotency:POST /orders:abc",
+            storage_key("POST", "/orders", "abc")
+        );
+    }
+}
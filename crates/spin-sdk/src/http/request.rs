@@ -0,0 +1,313 @@
+//! Convenience methods for inspecting and building HTTP requests.
+
+use std::borrow::Cow;
+
+use super::{EmptyBody, Error, FullBody, IntoResponse, MediaType, StatusCode};
+
+/// Extension trait providing convenience accessors for [`http::Request`](hyperium::Request).
+///
+/// This trait is implemented for requests with any body type, so it can be
+/// used both with incoming [`Request`](crate::http::Request)s and with
+/// requests being built for sending.
+pub trait RequestExt {
+    /// The type of the request body.
+    type Body;
+
+    /// Returns an iterator over the segments of the request path.
+    ///
+    /// Empty segments, including those produced by leading, trailing or
+    /// repeated slashes, are skipped, so `/a//b/` yields `"a"` and `"b"`.
+    /// The query string is not included.
+    ///
+    /// Each segment is percent-decoded. Decoding happens after splitting, so
+    /// an encoded slash (`%2F`) is kept within its segment rather than
+    /// starting a new one. Malformed escapes are left as they are, and
+    /// decoded bytes that are not valid UTF-8 are replaced with
+    /// `U+FFFD REPLACEMENT CHARACTER`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use spin_sdk::http::{Request, RequestExt};
+    ///
+    /// // For a request to `/users/jane%20doe/posts`
+    /// let segments: Vec<_> = request.path_segments().collect();
+    /// assert_eq!(segments, ["users", "jane doe", "posts"]);
+    /// ```
+    fn path_segments(&self) -> impl Iterator<Item = Cow<'_, str>>;
+
+    /// Separates the body from the request, returning the request with an
+    /// empty body in its place, and the body.
+    ///
+    /// This lets an extractor or middleware consume the body, then pass the
+    /// method, URI, headers and extensions on to later stages. A body can
+    /// only be read once, so those stages see an empty body; if they need
+    /// the contents too, pass them along separately or use
+    /// [`snapshot_request`](super::body::snapshot_request) instead.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use spin_sdk::http::{Request, RequestExt, body::IncomingBodyExt};
+    ///
+    /// let (request, body) = request.take_body();
+    /// let signature_ok = verify(request.headers(), &body.bytes().await?);
+    /// ```
+    fn take_body(self) -> (hyperium::Request<EmptyBody>, Self::Body);
+}
+
+impl<T> RequestExt for hyperium::Request<T> {
+    type Body = T;
+
+    fn path_segments(&self) -> impl Iterator<Item = Cow<'_, str>> {
+        path_segments(self.uri().path())
+    }
+
+    fn take_body(self) -> (hyperium::Request<EmptyBody>, T) {
+        let (parts, body) = self.into_parts();
+        (hyperium::Request::from_parts(parts, EmptyBody::new()), body)
+    }
+}
+
+/// Extension trait for finishing an [`http::request::Builder`](hyperium::request::Builder)
+/// with a body.
+///
+/// Together with the builder constructors such as
+/// [`Request::get`](hyperium::Request::get) and
+/// [`Request::post`](hyperium::Request::post), this makes it quick to
+/// construct requests for sending, or for testing handlers.
+///
+/// # Examples
+///
+/// ```ignore
+/// use spin_sdk::http::{Request, RequestBuilderExt};
+///
+/// #[derive(serde::Serialize)]
+/// struct NewUser { name: String }
+///
+/// let request = Request::post("https://example.com/users")
+///     .header("authorization", "Bearer secret")
+///     .json(&NewUser { name: "Alice".into() })?;
+/// let response = spin_sdk::http::send(request).await?;
+/// ```
+pub trait RequestBuilderExt {
+    /// Finishes the request with the given bytes as its body.
+    fn bytes(
+        self,
+        body: impl Into<bytes::Bytes>,
+    ) -> Result<hyperium::Request<FullBody<bytes::Bytes>>, Error>;
+
+    /// Finishes the request with `value` serialized as JSON as its body.
+    ///
+    /// The `Content-Type` header is set to `application/json` unless the
+    /// builder already has a `Content-Type`.
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    fn json<T: serde::Serialize + ?Sized>(
+        self,
+        value: &T,
+    ) -> Result<hyperium::Request<FullBody<bytes::Bytes>>, Error>;
+}
+
+impl RequestBuilderExt for hyperium::request::Builder {
+    fn bytes(
+        self,
+        body: impl Into<bytes::Bytes>,
+    ) -> Result<hyperium::Request<FullBody<bytes::Bytes>>, Error> {
+        Ok(self.body(FullBody::new(body.into()))?)
+    }
+
+    #[cfg(feature = "json")]
+    fn json<T: serde::Serialize + ?Sized>(
+        mut self,
+        value: &T,
+    ) -> Result<hyperium::Request<FullBody<bytes::Bytes>>, Error> {
+        let body = serde_json::to_vec(value).map_err(|e| Error::Other(Box::new(e)))?;
+        let has_content_type = self
+            .headers_ref()
+            .is_some_and(|h| h.contains_key(hyperium::header::CONTENT_TYPE));
+        if !has_content_type {
+            self = self.header(hyperium::header::CONTENT_TYPE, "application/json");
+        }
+        self.bytes(body)
+    }
+}
+
+/// Requires a request to have a particular `Content-Type`.
+///
+/// Call [`check`](Self::check) before reading the body to reject requests
+/// with the wrong media type early, instead of failing later during
+/// deserialization. The check compares the media type without parameters,
+/// ignoring case, so `application/json` accepts
+/// `application/json; charset=utf-8`. A required value ending in `/`, such
+/// as `text/`, accepts any subtype.
+///
+/// # Examples
+///
+/// ```ignore
+/// use spin_sdk::http::{IntoResponse, Request, RequireContentType, Result};
+/// use spin_sdk::http_service;
+///
+/// #[http_service]
+/// async fn handler(req: Request) -> Result<impl IntoResponse> {
+///     // Responds with `415 Unsupported Media Type` for anything else.
+///     RequireContentType("application/json").check(&req)?;
+///     Ok("Thanks for the JSON")
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RequireContentType(pub &'static str);
+
+impl RequireContentType {
+    /// Checks the `Content-Type` header of `request`.
+    ///
+    /// If the header is missing or does not match, the returned error is an
+    /// [`Error::Response`] holding a `415 Unsupported Media Type` response
+    /// that names the expected content type.
+    pub fn check<T>(&self, request: &hyperium::Request<T>) -> Result<(), Error> {
+        let content_type = request
+            .headers()
+            .get(hyperium::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok());
+        if content_type_matches(content_type, self.0) {
+            return Ok(());
+        }
+        let message = match content_type {
+            Some(actual) => format!("expected content type `{}`, got `{actual}`", self.0),
+            None => format!("expected content type `{}`", self.0),
+        };
+        match (StatusCode::UNSUPPORTED_MEDIA_TYPE, message).into_response() {
+            Ok(response) => Err(Error::Response(response)),
+            Err(code) => Err(Error::ErrorCode(code)),
+        }
+    }
+}
+
+fn content_type_matches(content_type: Option<&str>, required: &str) -> bool {
+    content_type
+        .and_then(|ct| ct.parse::<MediaType>().ok())
+        .is_some_and(|ct| ct.matches(required))
+}
+
+fn path_segments(path: &str) -> impl Iterator<Item = Cow<'_, str>> {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(percent_decode)
+}
+
+/// Decodes `%XX` escapes in `s`, leaving malformed escapes untouched.
+fn percent_decode(s: &str) -> Cow<'_, str> {
+    if !s.contains('%') {
+        return Cow::Borrowed(s);
+    }
+
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(hex) = bytes.get(i + 1..i + 3)
+            && hex.iter().all(u8::is_ascii_hexdigit)
+        {
+            let hex = std::str::from_utf8(hex).unwrap();
+            decoded.push(u8::from_str_radix(hex, 16).unwrap());
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    match String::from_utf8_lossy(&decoded) {
+        Cow::Borrowed(s) => Cow::Owned(s.to_owned()),
+        Cow::Owned(s) => Cow::Owned(s),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn segments() {
+        assert_eq!(
+            Vec::<Cow<str>>::new(),
+            path_segments("/").collect::<Vec<_>>()
+        );
+        assert_eq!(vec!["a", "b"], path_segments("/a//b/").collect::<Vec<_>>());
+        assert_eq!(
+            vec!["users", "jane doe", "a/b", "100%"],
+            path_segments("/users/jane%20doe/a%2Fb/100%").collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec!["%+1", "%zz"],
+            path_segments("/%+1/%zz").collect::<Vec<_>>()
+        );
+    }
+
+    fn body_bytes(request: hyperium::Request<FullBody<bytes::Bytes>>) -> bytes::Bytes {
+        use http_body_util::BodyExt;
+        let collected = futures::executor::block_on(request.into_body().collect());
+        collected.unwrap().to_bytes()
+    }
+
+    #[test]
+    fn take_body() {
+        let request = hyperium::Request::post("/upload")
+            .header("x-signature", "abc")
+            .body("payload")
+            .unwrap();
+        let (request, body) = request.take_body();
+        assert_eq!("payload", body);
+        assert_eq!("/upload", request.uri());
+        assert_eq!("abc", request.headers()["x-signature"]);
+        assert!(http_body::Body::is_end_stream(request.body()));
+    }
+
+    #[test]
+    fn build_with_body() {
+        let request = hyperium::Request::put("/items/1").bytes("data").unwrap();
+        assert_eq!(hyperium::Method::PUT, request.method());
+        assert_eq!("data", body_bytes(request));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn build_with_json() {
+        let request = hyperium::Request::post("/items")
+            .json(&serde_json::json!({"name": "widget"}))
+            .unwrap();
+        assert_eq!(
+            "application/json",
+            request.headers()[hyperium::header::CONTENT_TYPE]
+        );
+        assert_eq!(r#"{"name":"widget"}"#, body_bytes(request));
+
+        let request = hyperium::Request::post("/items")
+            .header("content-type", "application/vnd.api+json")
+            .json(&[1, 2])
+            .unwrap();
+        assert_eq!(
+            "application/vnd.api+json",
+            request.headers()[hyperium::header::CONTENT_TYPE]
+        );
+    }
+
+    #[test]
+    fn content_types() {
+        let json = "application/json";
+        assert!(content_type_matches(Some("application/json"), json));
+        assert!(content_type_matches(
+            Some("Application/JSON; charset=utf-8"),
+            json
+        ));
+        assert!(!content_type_matches(
+            Some("application/json-patch+json"),
+            json
+        ));
+        assert!(!content_type_matches(Some("text/plain"), json));
+        assert!(!content_type_matches(None, json));
+        assert!(content_type_matches(Some("text/csv"), "text/"));
+        assert!(!content_type_matches(Some("textual/csv"), "text/"));
+    }
+}
@@ -0,0 +1,73 @@
+//! Convenience methods for consuming HTTP responses.
+
+use super::{Error, HeaderExt};
+use wasip3::http_compat::{IncomingBody, IncomingMessage};
+
+/// Extension trait providing convenience methods for responses received from
+/// [`send`](super::send).
+///
+/// # Examples
+///
+/// ```ignore
+/// use spin_sdk::http::{EmptyBody, ResponseExt, send_expecting_success};
+///
+/// #[derive(serde::Deserialize)]
+/// struct User { name: String }
+///
+/// let request = http::Request::get("https://example.com/users/1").body(EmptyBody::new())?;
+/// let user: User = send_expecting_success(request).await?.json().await?;
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+#[allow(async_fn_in_trait)]
+pub trait ResponseExt {
+    /// Reads the whole body and deserializes it as JSON.
+    ///
+    /// If the response has a `Content-Type` header, it must be
+    /// `application/json` or a `+json` type such as
+    /// `application/problem+json`; a response without one is read as JSON
+    /// anyway. The status code is not checked, so an error response with a
+    /// JSON body is deserialized like any other; use
+    /// [`send_expecting_success`](super::send_expecting_success) to reject
+    /// those first.
+    async fn json<T: serde::de::DeserializeOwned>(self) -> Result<T, Error>;
+}
+
+impl<T: IncomingMessage> ResponseExt for hyperium::Response<IncomingBody<T>> {
+    async fn json<U: serde::de::DeserializeOwned>(self) -> Result<U, Error> {
+        use super::body::IncomingBodyExt;
+
+        check_json(self.header_str(hyperium::header::CONTENT_TYPE))?;
+        let body = self.into_body().bytes().await?;
+        serde_json::from_slice(&body).map_err(|e| Error::Other(e.into()))
+    }
+}
+
+fn check_json(content_type: Option<&str>) -> Result<(), Error> {
+    let Some(content_type) = content_type else {
+        return Ok(());
+    };
+    let is_json = content_type
+        .parse::<super::MediaType>()
+        .is_ok_and(|ct| ct.essence() == "application/json" || ct.subtype().ends_with("+json"));
+    if is_json {
+        Ok(())
+    } else {
+        Err(format!("expected a JSON response, got content type `{content_type}`").into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn json_content_types() {
+        assert!(check_json(None).is_ok());
+        assert!(check_json(Some("application/json")).is_ok());
+        assert!(check_json(Some("Application/JSON; charset=utf-8")).is_ok());
+        assert!(check_json(Some("application/problem+json")).is_ok());
+        assert!(check_json(Some("text/html")).is_err());
+        assert!(check_json(Some("application/jsonp")).is_err());
+        assert!(check_json(Some("not a media type")).is_err());
+    }
+}
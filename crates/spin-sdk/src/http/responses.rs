@@ -0,0 +1,72 @@
+//! Constructors for common responses.
+//!
+//! Each function returns an [`http::Response`](hyperium::Response), which
+//! implements [`IntoResponse`](super::IntoResponse) and can be returned from
+//! a handler as it is or adjusted further first.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use spin_sdk::http::{IntoResponse, Request, responses};
+//! use spin_sdk::http_service;
+//! use std::time::Duration;
+//!
+//! #[http_service]
+//! async fn handler(_req: Request) -> impl IntoResponse {
+//!     responses::too_many_requests(Duration::from_secs(30))
+//! }
+//! ```
+
+use super::EmptyBody;
+use hyperium::{HeaderValue, StatusCode, header};
+use std::time::Duration;
+
+/// A `429 Too Many Requests` response, asking the client to wait for
+/// `retry_after` before trying again.
+///
+/// The `Retry-After` header is given in whole seconds, rounding up, so that a
+/// client that honours it never retries early.
+pub fn too_many_requests(retry_after: Duration) -> hyperium::Response<EmptyBody> {
+    retry_later(StatusCode::TOO_MANY_REQUESTS, retry_after)
+}
+
+/// A `503 Service Unavailable` response, asking the client to wait for
+/// `retry_after` before trying again.
+///
+/// The `Retry-After` header is formatted as for [`too_many_requests`].
+pub fn service_unavailable(retry_after: Duration) -> hyperium::Response<EmptyBody> {
+    retry_later(StatusCode::SERVICE_UNAVAILABLE, retry_after)
+}
+
+fn retry_later(status: StatusCode, retry_after: Duration) -> hyperium::Response<EmptyBody> {
+    let mut response = hyperium::Response::new(EmptyBody::new());
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, delta_seconds(retry_after));
+    response
+}
+
+/// Formats a duration as the delta-seconds form of `Retry-After`.
+fn delta_seconds(duration: Duration) -> HeaderValue {
+    let seconds = duration.as_secs() + u64::from(duration.subsec_nanos() > 0);
+    HeaderValue::from(seconds)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn retry_after() {
+        let response = too_many_requests(Duration::from_secs(30));
+        assert_eq!(StatusCode::TOO_MANY_REQUESTS, response.status());
+        assert_eq!("30", response.headers()[header::RETRY_AFTER]);
+
+        let response = service_unavailable(Duration::from_millis(1500));
+        assert_eq!(StatusCode::SERVICE_UNAVAILABLE, response.status());
+        assert_eq!("2", response.headers()[header::RETRY_AFTER]);
+
+        assert_eq!("0", delta_seconds(Duration::ZERO));
+    }
+}
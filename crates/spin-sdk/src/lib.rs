@@ -15,6 +15,10 @@
 // Re-export entrypoint macros
 pub use spin_macro::{dependencies, http_service, redis_subscriber};
 
+// Lets derive macros, which refer to `::spin_sdk`, be used in unit tests.
+#[cfg(test)]
+extern crate self as spin_sdk;
+
 /// Incoming and outgoing HTTP requests.
 #[cfg(feature = "http")]
 #[cfg_attr(docsrs, doc(cfg(feature = "http")))]
@@ -50,6 +54,21 @@ pub mod pg;
 #[cfg_attr(docsrs, doc(cfg(feature = "redis")))]
 pub mod redis;
 
+#[cfg(any(feature = "mysql", feature = "pg"))]
+mod db_value;
+
+#[cfg(any(feature = "mysql", feature = "pg"))]
+mod redact;
+
+#[cfg(all(
+    feature = "json",
+    any(feature = "mysql", feature = "pg", feature = "sqlite")
+))]
+mod db_json;
+
+/// Retrying operations that may fail transiently.
+pub mod retry;
+
 /// SQLite database access.
 #[cfg(feature = "sqlite")]
 #[cfg_attr(docsrs, doc(cfg(feature = "sqlite")))]
@@ -78,6 +97,49 @@ extern "C" fn __spin_sdk_hash() {}
 
 pub use wasip3::{self, wit_bindgen};
 
+/// Generates Rust bindings for a WIT world, like
+/// [`wit_bindgen::generate!`], using the `wit-bindgen` runtime re-exported by
+/// this crate.
+///
+/// The generated code refers to the runtime through `runtime_path`, which
+/// `wit_bindgen::generate!` defaults to the `wit_bindgen` crate. This macro
+/// sets it to `::spin_sdk::wit_bindgen::rt`, so a component does not need a
+/// direct dependency on `wit-bindgen`. Do not pass `runtime_path` yourself.
+///
+/// # Examples
+///
+/// ```ignore
+/// spin_sdk::generate!({
+///     inline: "
+///         package example:greeter;
+///
+///         world greeter {
+///             import greet: func(name: string) -> string;
+///         }
+///     ",
+///     world: "greeter",
+/// });
+/// ```
+#[macro_export]
+macro_rules! generate {
+    ({ $($options:tt)* }) => {
+        $crate::wit_bindgen::generate!({
+            runtime_path: "::spin_sdk::wit_bindgen::rt",
+            $($options)*
+        });
+    };
+    ($world:literal) => {
+        $crate::generate!({ world: $world });
+    };
+}
+
+/// Dependencies used by code generated by the SDK macros.
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "json")]
+    pub use serde_json;
+}
+
 #[doc(hidden)]
 pub mod experimental {
     #![allow(missing_docs)]
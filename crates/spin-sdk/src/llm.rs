@@ -16,6 +16,8 @@ pub mod wit {
     pub use fermyon::spin::llm;
 }
 
+use std::{borrow::Borrow, collections::HashMap, hash::Hash};
+
 pub use wit::llm::{Error, InferencingParams, InferencingResult, InferencingUsage};
 
 /// Provides access to the underlying WIT interface. You should not normally need
@@ -96,6 +98,52 @@ impl Default for InferencingParams {
     }
 }
 
+/// Why a model stopped generating text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FinishReason {
+    /// The model finished its answer.
+    Stop,
+    /// The model reached the `max_tokens` limit, so the text is probably
+    /// cut short.
+    Length,
+}
+
+impl InferencingResult {
+    /// Works out why generation stopped, given the `max_tokens` the request
+    /// was made with.
+    ///
+    /// The host does not report a finish reason, so it is inferred from the
+    /// usage: a result that used all of `max_tokens` is treated as
+    /// truncated. [`infer`] uses the `max_tokens` of
+    /// [`InferencingParams::default`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use spin_sdk::llm::{FinishReason, InferencingModel, InferencingParams, infer_with_options};
+    ///
+    /// # fn run() -> anyhow::Result<()> {
+    /// let params = InferencingParams { max_tokens: 200, ..Default::default() };
+    /// let result = infer_with_options(InferencingModel::Llama2Chat, "Tell me a story", params)?;
+    /// println!(
+    ///     "{} prompt tokens, {} generated",
+    ///     result.usage.prompt_token_count, result.usage.generated_token_count
+    /// );
+    /// if result.finish_reason(params.max_tokens) == FinishReason::Length {
+    ///     println!("the story was cut short");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn finish_reason(&self, max_tokens: u32) -> FinishReason {
+        if self.usage.generated_token_count >= max_tokens {
+            FinishReason::Length
+        } else {
+            FinishReason::Stop
+        }
+    }
+}
+
 /// Perform inferencing using the provided model and prompt
 pub fn infer(model: InferencingModel, prompt: &str) -> Result<InferencingResult, Error> {
     llm::infer(&model.to_string(), prompt, None)
@@ -110,6 +158,140 @@ pub fn infer_with_options(
     llm::infer(&model.to_string(), prompt, Some(options))
 }
 
+/// The author of a [`ChatMessage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatRole {
+    /// Instructions that set the behaviour of the assistant.
+    System,
+    /// A message from the user.
+    User,
+    /// A previous reply from the assistant.
+    Assistant,
+}
+
+/// A message in a conversation with a chat model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatMessage {
+    /// Who the message is from.
+    pub role: ChatRole,
+    /// The text of the message.
+    pub content: String,
+}
+
+impl ChatMessage {
+    /// A system message, giving instructions to the assistant.
+    pub fn system(content: impl Into<String>) -> Self {
+        Self {
+            role: ChatRole::System,
+            content: content.into(),
+        }
+    }
+
+    /// A message from the user.
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: ChatRole::User,
+            content: content.into(),
+        }
+    }
+
+    /// A previous reply from the assistant.
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self {
+            role: ChatRole::Assistant,
+            content: content.into(),
+        }
+    }
+}
+
+/// Continues a conversation with a chat model, returning the assistant's reply.
+///
+/// The messages are assembled into a single prompt in the format the model
+/// was trained on: the `[INST]` format for [`InferencingModel::Llama2Chat`]
+/// and [`InferencingModel::Codellarunstruct`], and a plain `Role: message`
+/// transcript for other models. System messages apply to the whole
+/// conversation, and the last message must be from the user.
+///
+/// Returns [`Error::InvalidInput`] if there is no user message to reply to.
+///
+/// # Examples
+///
+/// ```no_run
+/// use spin_sdk::llm::{chat, ChatMessage, InferencingModel, InferencingParams};
+///
+/// # fn run() -> anyhow::Result<()> {
+/// let reply = chat(
+///     InferencingModel::Llama2Chat,
+///     &[
+///         ChatMessage::system("You are a helpful gardening assistant."),
+///         ChatMessage::user("When should I plant turnips?"),
+///     ],
+///     InferencingParams::default(),
+/// )?;
+/// println!("{reply}");
+/// # Ok(())
+/// # }
+/// ```
+pub fn chat(
+    model: InferencingModel,
+    messages: &[ChatMessage],
+    options: InferencingParams,
+) -> Result<String, Error> {
+    let prompt = chat_prompt(model, messages)?;
+    let result = infer_with_options(model, &prompt, options)?;
+    Ok(result.text.trim().to_owned())
+}
+
+fn chat_prompt(model: InferencingModel, messages: &[ChatMessage]) -> Result<String, Error> {
+    let last = messages.iter().rfind(|m| m.role != ChatRole::System);
+    if last.is_none_or(|m| m.role != ChatRole::User) {
+        return Err(Error::InvalidInput(
+            "a chat must end with a user message".to_owned(),
+        ));
+    }
+
+    let system = messages
+        .iter()
+        .filter(|m| m.role == ChatRole::System)
+        .map(|m| m.content.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let turns = messages.iter().filter(|m| m.role != ChatRole::System);
+
+    let mut prompt = String::new();
+    match model {
+        InferencingModel::Llama2Chat | InferencingModel::Codellarunstruct => {
+            let mut system = (!system.is_empty()).then_some(system);
+            for message in turns {
+                if message.role == ChatRole::User {
+                    prompt.push_str("<s>[INST] ");
+                    if let Some(system) = system.take() {
+                        prompt.push_str(&format!("<<SYS>>\n{system}\n<</SYS>>\n\n"));
+                    }
+                    prompt.push_str(message.content.trim());
+                    prompt.push_str(" [/INST]");
+                } else {
+                    prompt.push_str(&format!(" {} </s>", message.content.trim()));
+                }
+            }
+        }
+        InferencingModel::Other(_) => {
+            if !system.is_empty() {
+                prompt.push_str(&format!("System: {system}\n"));
+            }
+            for message in turns {
+                let role = match message.role {
+                    ChatRole::User => "User",
+                    _ => "Assistant",
+                };
+                prompt.push_str(&format!("{role}: {}\n", message.content.trim()));
+            }
+            prompt.push_str("Assistant:");
+        }
+    }
+    Ok(prompt)
+}
+
 /// Model used for generating embeddings
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy)]
@@ -135,3 +317,254 @@ pub fn generate_embeddings(
 ) -> Result<llm::EmbeddingsResult, Error> {
     llm::generate_embeddings(&model.to_string(), text)
 }
+
+/// A prompt with `{{name}}` placeholders that are filled in when it is rendered.
+///
+/// Placeholder names may be surrounded by whitespace (`{{ name }}`). An
+/// unterminated `{{` is kept as literal text.
+///
+/// Substitution happens in a single pass: values are inserted verbatim and
+/// are never themselves scanned for placeholders, so text supplied by a user
+/// cannot expand other variables.
+///
+/// # Examples
+///
+/// ```no_run
+/// use spin_sdk::llm::{infer, InferencingModel, PromptTemplate};
+/// use std::collections::HashMap;
+///
+/// # fn run() -> anyhow::Result<()> {
+/// let template = PromptTemplate::new("Translate the following into {{language}}:\n{{text}}");
+/// let vars = HashMap::from([("language", "French"), ("text", "Hello, world!")]);
+/// let prompt = template.render(&vars)?;
+///
+/// let result = infer(InferencingModel::Llama2Chat, &prompt)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct PromptTemplate {
+    template: String,
+}
+
+impl PromptTemplate {
+    /// Creates a template from text containing `{{name}}` placeholders.
+    pub fn new(template: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+        }
+    }
+
+    /// The names of the placeholders in the template, in order of appearance.
+    pub fn variables(&self) -> impl Iterator<Item = &str> {
+        self.parts().filter_map(|part| match part {
+            TemplatePart::Variable(name) => Some(name),
+            TemplatePart::Text(_) => None,
+        })
+    }
+
+    /// Renders the template, replacing each placeholder with its value from `vars`.
+    ///
+    /// Returns [`Error::InvalidInput`] if a placeholder has no value in `vars`.
+    pub fn render<K, V>(&self, vars: &HashMap<K, V>) -> Result<String, Error>
+    where
+        K: Borrow<str> + Hash + Eq,
+        V: AsRef<str>,
+    {
+        let mut prompt = String::with_capacity(self.template.len());
+        for part in self.parts() {
+            match part {
+                TemplatePart::Text(text) => prompt.push_str(text),
+                TemplatePart::Variable(name) => {
+                    let value = vars.get(name).ok_or_else(|| {
+                        Error::InvalidInput(format!("no value for prompt variable `{name}`"))
+                    })?;
+                    prompt.push_str(value.as_ref());
+                }
+            }
+        }
+        Ok(prompt)
+    }
+
+    fn parts(&self) -> impl Iterator<Item = TemplatePart<'_>> {
+        let mut rest = self.template.as_str();
+        std::iter::from_fn(move || {
+            if rest.is_empty() {
+                return None;
+            }
+            let part = match rest.find("{{") {
+                Some(0) => match rest[2..].find("}}") {
+                    Some(end) => {
+                        let name = &rest[2..2 + end];
+                        rest = &rest[2 + end + 2..];
+                        TemplatePart::Variable(name.trim())
+                    }
+                    None => {
+                        let text = rest;
+                        rest = "";
+                        TemplatePart::Text(text)
+                    }
+                },
+                Some(start) => {
+                    let text = &rest[..start];
+                    rest = &rest[start..];
+                    TemplatePart::Text(text)
+                }
+                None => {
+                    let text = rest;
+                    rest = "";
+                    TemplatePart::Text(text)
+                }
+            };
+            Some(part)
+        })
+    }
+}
+
+enum TemplatePart<'a> {
+    Text(&'a str),
+    Variable(&'a str),
+}
+
+/// Shortens `prompt` to at most `max_chars` characters.
+///
+/// The prompt is cut on a character boundary, so the result is always valid
+/// UTF-8. This is useful for keeping user-supplied text within a model's
+/// context budget before it is substituted into a [`PromptTemplate`].
+pub fn truncate_prompt(prompt: &str, max_chars: usize) -> &str {
+    match prompt.char_indices().nth(max_chars) {
+        Some((end, _)) => &prompt[..end],
+        None => prompt,
+    }
+}
+
+/// Estimates the number of tokens a model would split `text` into.
+///
+/// Models use their own tokenizers, which are not available to the SDK, so
+/// this is an approximation: runs of ASCII letters and digits count as one
+/// token per four characters, and every other non-whitespace character,
+/// including punctuation and non-ASCII characters, counts as one token.
+/// This is close to common tokenizers for English prose, but can be off in
+/// either direction for code, unusual words or other scripts. Leave headroom
+/// when comparing it against a hard limit.
+pub fn estimate_tokens(text: &str) -> usize {
+    token_ends(text).count()
+}
+
+/// Shortens `text` to at most `max_tokens` tokens, as counted by
+/// [`estimate_tokens`].
+///
+/// The text is cut on a character boundary, so the result is always valid
+/// UTF-8. Like the estimate itself, the cut point is approximate.
+pub fn truncate_to_tokens(text: &str, max_tokens: usize) -> &str {
+    if max_tokens == 0 {
+        return "";
+    }
+    match token_ends(text).nth(max_tokens - 1) {
+        Some(end) => &text[..end],
+        None => text,
+    }
+}
+
+/// The byte offsets at which each estimated token in `text` ends.
+fn token_ends(text: &str) -> impl Iterator<Item = usize> + '_ {
+    const CHARS_PER_WORD_TOKEN: usize = 4;
+
+    let mut chars = text.char_indices().peekable();
+    std::iter::from_fn(move || {
+        let (start, c) = chars.find(|(_, c)| !c.is_whitespace())?;
+        if !c.is_ascii_alphanumeric() {
+            return Some(start + c.len_utf8());
+        }
+        let mut end = start + 1;
+        for _ in 1..CHARS_PER_WORD_TOKEN {
+            match chars.next_if(|(_, c)| c.is_ascii_alphanumeric()) {
+                Some((i, _)) => end = i + 1,
+                None => break,
+            }
+        }
+        Some(end)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn render_template() {
+        let template = PromptTemplate::new("Say {{ greeting }} to {{name}}{{name}}!");
+        assert_eq!(
+            vec!["greeting", "name", "name"],
+            template.variables().collect::<Vec<_>>()
+        );
+
+        let vars = HashMap::from([("greeting", "hello"), ("name", "{{greeting}}")]);
+        assert_eq!(
+            "Say hello to {{greeting}}{{greeting}}!",
+            template.render(&vars).unwrap()
+        );
+
+        let vars = HashMap::from([("greeting", "hello")]);
+        assert!(template.render(&vars).is_err());
+
+        let unterminated = PromptTemplate::new("{{a}} and {{b");
+        let vars = HashMap::from([("a".to_owned(), "x".to_owned())]);
+        assert_eq!("x and {{b", unterminated.render(&vars).unwrap());
+    }
+
+    #[test]
+    fn finish_reasons() {
+        let result = |generated_token_count| InferencingResult {
+            text: String::new(),
+            usage: InferencingUsage {
+                prompt_token_count: 10,
+                generated_token_count,
+            },
+        };
+        assert_eq!(FinishReason::Stop, result(42).finish_reason(100));
+        assert_eq!(FinishReason::Length, result(100).finish_reason(100));
+    }
+
+    #[test]
+    fn truncate() {
+        assert_eq!("héllo", truncate_prompt("héllo", 10));
+        assert_eq!("hé", truncate_prompt("héllo", 2));
+        assert_eq!("", truncate_prompt("héllo", 0));
+    }
+
+    #[test]
+    fn chat_prompts() {
+        let messages = [
+            ChatMessage::system("Be brief."),
+            ChatMessage::user("Hi"),
+            ChatMessage::assistant("Hello!"),
+            ChatMessage::user("Name a vegetable."),
+        ];
+        assert_eq!(
+            "<s>[INST] <<SYS>>\nBe brief.\n<</SYS>>\n\nHi [/INST] Hello! </s><s>[INST] Name a vegetable. [/INST]",
+            chat_prompt(InferencingModel::Llama2Chat, &messages).unwrap()
+        );
+        assert_eq!(
+            "System: Be brief.\nUser: Hi\nAssistant: Hello!\nUser: Name a vegetable.\nAssistant:",
+            chat_prompt(InferencingModel::Other("my-model"), &messages).unwrap()
+        );
+        assert!(chat_prompt(InferencingModel::Llama2Chat, &messages[..3]).is_err());
+        assert!(chat_prompt(InferencingModel::Llama2Chat, &messages[..1]).is_err());
+    }
+
+    #[test]
+    fn token_estimates() {
+        assert_eq!(0, estimate_tokens(" \n "));
+        assert_eq!(1, estimate_tokens("the"));
+        assert_eq!(2, estimate_tokens("turnip"));
+        assert_eq!(6, estimate_tokens("Hello, world!"));
+        assert_eq!(2, estimate_tokens("日本"));
+
+        assert_eq!("Hello, world", truncate_to_tokens("Hello, world!", 5));
+        assert_eq!("Hell", truncate_to_tokens("Hello, world!", 1));
+        assert_eq!("日", truncate_to_tokens("日本", 1));
+        assert_eq!("", truncate_to_tokens("Hello", 0));
+        assert_eq!("Hello", truncate_to_tokens("Hello", 10));
+    }
+}
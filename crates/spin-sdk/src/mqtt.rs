@@ -42,6 +42,10 @@ pub mod wit {
     pub use spin::mqtt::mqtt;
 }
 
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
 /// An open connection to an MQTT queue.
 ///
 /// The address must be in URL form, and must include a `client_id`:
@@ -72,6 +76,20 @@ pub struct Connection(wit::mqtt::Connection);
 
 pub use wit::mqtt::{Error, Payload, Qos};
 
+/// The settings a shared connection was opened with.
+#[derive(PartialEq, Eq, Hash)]
+struct SharedKey {
+    address: String,
+    username: String,
+    password: String,
+    keep_alive_interval_in_secs: u64,
+}
+
+thread_local! {
+    /// Connections opened by [`Connection::open_shared`], by their settings.
+    static SHARED: RefCell<HashMap<SharedKey, Rc<Connection>>> = RefCell::default();
+}
+
 impl Connection {
     /// Open a connection to the Mqtt instance at `address`.
     pub async fn open(
@@ -90,6 +108,51 @@ impl Connection {
         .map(Connection)
     }
 
+    /// Open a connection to the Mqtt instance at `address`, or return the
+    /// one already opened with the same settings by an earlier call.
+    ///
+    /// Use this when one invocation publishes from several places, such as
+    /// in a loop, so that each call does not open its own connection. Shared
+    /// connections stay open until the component instance exits, which Spin
+    /// does after each request or message, or until
+    /// [`clear_shared`](Self::clear_shared) is called. A connection that has
+    /// failed is not replaced automatically, so clear it before opening
+    /// again.
+    pub async fn open_shared(
+        address: impl AsRef<str>,
+        username: impl AsRef<str>,
+        password: impl AsRef<str>,
+        keep_alive_interval_in_secs: u64,
+    ) -> Result<Rc<Self>, Error> {
+        let key = SharedKey {
+            address: address.as_ref().to_owned(),
+            username: username.as_ref().to_owned(),
+            password: password.as_ref().to_owned(),
+            keep_alive_interval_in_secs,
+        };
+        if let Some(conn) = SHARED.with_borrow(|shared| shared.get(&key).cloned()) {
+            return Ok(conn);
+        }
+        let conn = Rc::new(
+            Self::open(
+                &key.address,
+                &key.username,
+                &key.password,
+                keep_alive_interval_in_secs,
+            )
+            .await?,
+        );
+        SHARED.with_borrow_mut(|shared| shared.insert(key, conn.clone()));
+        Ok(conn)
+    }
+
+    /// Forget the connections opened by [`open_shared`](Self::open_shared).
+    ///
+    /// Each connection is closed once the last handle to it is dropped.
+    pub fn clear_shared() {
+        SHARED.with_borrow_mut(HashMap::clear);
+    }
+
     /// Publish an Mqtt message to the specified `topic`.
     pub async fn publish(
         &self,
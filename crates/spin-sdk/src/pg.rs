@@ -7,33 +7,85 @@
 //!
 //! # Types
 //!
-//! | Rust type               | WIT (db-value)                                | Postgres type(s)             |
-//! |-------------------------|-----------------------------------------------|----------------------------- |
-//! | `bool`                  | boolean(bool)                                 | BOOL                         |
-//! | `i16`                   | int16(s16)                                    | SMALLINT, SMALLSERIAL, INT2  |
-//! | `i32`                   | int32(s32)                                    | INT, SERIAL, INT4            |
-//! | `i64`                   | int64(s64)                                    | BIGINT, BIGSERIAL, INT8      |
-//! | `f32`                   | floating32(float32)                           | REAL, FLOAT4                 |
-//! | `f64`                   | floating64(float64)                           | DOUBLE PRECISION, FLOAT8     |
-//! | `String`                | str(string)                                   | VARCHAR, CHAR(N), TEXT       |
-//! | `Vec<u8>`               | binary(list\<u8\>)                            | BYTEA                        |
-//! | `chrono::NaiveDate`     | date(tuple<s32, u8, u8>)                      | DATE                         |
-//! | `chrono::NaiveTime`     | time(tuple<u8, u8, u8, u32>)                  | TIME                         |
-//! | `chrono::NaiveDateTime` | datetime(tuple<s32, u8, u8, u8, u8, u8, u32>) | TIMESTAMP                    |
-//! | `chrono::Duration`      | timestamp(s64)                                | BIGINT                       |
-//! | `uuid::Uuid`            | uuid(string)                                  | UUID                         |
-//! | `serde_json::Value`     | jsonb(list\<u8\>)                             | JSONB                        |
-//! | `serde::De/Serialize`   | jsonb(list\<u8\>)                             | JSONB                        |
-//! | `rust_decimal::Decimal` | decimal(string)                               | NUMERIC                      |
-//! | `postgres_range`        | range-int32(...), range-int64(...)            | INT4RANGE, INT8RANGE         |
-//! | lower/upper tuple       | range-decimal(...)                            | NUMERICRANGE                 |
-//! | `Vec<Option<...>>`      | array-int32(...), array-int64(...), array-str(...), array-decimal(...) | INT4[], INT8[], TEXT[], NUMERIC[] |
-//! | `pg4::Interval`         | interval(interval)                            | INTERVAL                     |
+//! | Rust type                 | WIT (db-value)                                     | Postgres type(s)                    |
+//! |---------------------------|----------------------------------------------------|-------------------------------------|
+//! | `bool`                    | boolean(bool)                                      | BOOL                                |
+//! | `i16`                     | int16(s16)                                         | SMALLINT, SMALLSERIAL, INT2         |
+//! | `i32`                     | int32(s32)                                         | INT, SERIAL, INT4                   |
+//! | `i64`                     | int64(s64)                                         | BIGINT, BIGSERIAL, INT8             |
+//! | `f32`                     | floating32(float32)                                | REAL, FLOAT4                        |
+//! | `f64`                     | floating64(float64)                                | DOUBLE PRECISION, FLOAT8            |
+//! | `String`                  | str(string)                                        | VARCHAR, CHAR(N), TEXT, CITEXT      |
+//! | `Vec<u8>`                 | binary(list\<u8\>)                                 | BYTEA                               |
+//! | `[u8; N]`                 | binary(list\<u8\>)                                 | BYTEA (exactly N bytes)             |
+//! | `chrono::NaiveDate`       | date(tuple<s32, u8, u8>)                           | DATE                                |
+//! | `chrono::NaiveTime`       | time(tuple<u8, u8, u8, u32>)                       | TIME                                |
+//! | `chrono::NaiveDateTime`   | datetime(tuple<s32, u8, u8, u8, u8, u8, u32>)      | TIMESTAMP                           |
+//! | `chrono::Duration`        | timestamp(s64)                                     | BIGINT                              |
+//! | `time::Date`              | date(tuple<s32, u8, u8>)                           | DATE                                |
+//! | `time::Time`              | time(tuple<u8, u8, u8, u32>)                       | TIME                                |
+//! | `time::PrimitiveDateTime` | datetime(tuple<s32, u8, u8, u8, u8, u8, u32>)      | TIMESTAMP                           |
+//! | `time::OffsetDateTime`    | datetime(tuple<s32, u8, u8, u8, u8, u8, u32>)      | TIMESTAMPTZ (as UTC)                |
+//! | `uuid::Uuid`              | uuid(string)                                       | UUID                                |
+//! | `serde_json::Value`       | jsonb(list\<u8\>)                                  | JSONB                               |
+//! | `serde::De/Serialize`     | jsonb(list\<u8\>)                                  | JSONB                               |
+//! | `rust_decimal::Decimal`   | decimal(string)                                    | NUMERIC                             |
+//! | `postgres_range`          | range-int32(...), range-int64(...)                 | INT4RANGE, INT8RANGE                |
+//! | `postgres_range`          | str(string)                                        | TSRANGE (select as TEXT)            |
+//! | lower/upper tuple         | range-decimal(...)                                 | NUMERICRANGE                        |
+//! | lower/upper tuple         | str(string)                                        | TSRANGE, DATERANGE (select as TEXT) |
+//! | `Vec<Option<...>>`        | array-int32, array-int64, array-str, array-decimal | INT4[], INT8[], TEXT[], NUMERIC[]   |
+//! | `pg4::Interval`           | interval(interval)                                 | INTERVAL                            |
+//!
+//! There are no WIT array types for SMALLINT, REAL, DOUBLE PRECISION or BOOL
+//! arrays, so these are carried by the nearest available array type:
+//!
+//! | Rust type               | WIT (db-value)     | Postgres type(s)             |
+//! |-------------------------|--------------------|------------------------------|
+//! | `Vec<Option<i16>>`      | array-int32(...)   | INT2[] (read directly)       |
+//! | `Vec<Option<f32>>`      | array-decimal(...) | REAL[] (select as NUMERIC[]) |
+//! | `Vec<Option<f64>>`      | array-decimal(...) | FLOAT8[] (select as NUMERIC[]) |
+//! | `Vec<Option<bool>>`     | array-str(...)     | BOOL[] (select as TEXT[])    |
+//! | `Vec<Option<Vec<u8>>>`  | array-str(...)     | BYTEA[] (select as TEXT[])   |
+//!
+//! When selecting these columns, cast them as shown, for example
+//! `SELECT scores::numeric[]` or `SELECT flags::text[]`. When passing them as
+//! parameters, INT2, REAL and FLOAT8 arrays are converted on assignment, but
+//! BOOL and BYTEA arrays must be cast in the statement, for example
+//! `$1::boolean[]` or `$1::text[]::bytea[]`. BYTEA array elements are parsed
+//! with [`decode_bytea`], and are passed in the hex format.
+//!
+//! NUMERIC values can also be read as a lossy `f64` with [`decode_numeric_as_f64`].
+//! BIT and VARBIT values, selected as text, can be read with [`decode_bits`]
+//! and passed with [`encode_bits`].
+//! Composite (row) values, selected as text, can be split into their fields
+//! with [`decode_record`].
+//! MONEY values, selected as text or NUMERIC, can be read with `decode_money`.
+//!
+//! CITEXT values decode as `String` with their original case preserved; only
+//! comparisons in the database are case-insensitive.
+//!
+//! To read whole rows into structs, derive [`FromRow`] and use
+//! [`QueryResult::into_typed`].
+//!
+//! To show values whose types are not known in advance, render them with
+//! [`display_value`] or convert them with `to_json_value` (`json` feature).
+//!
+//! # Cancellation
+//!
+//! Dropping a pending [`Connection::query`] or [`Connection::execute`]
+//! future, or a [`QueryResult`] before all of its rows have been read, tells
+//! the host that the result is no longer wanted. This happens, for example,
+//! when the query is wrapped in [`crate::time::timeout`] or the handler is
+//! cut short by `#[http_service(timeout_ms = ...)]`. Whether the statement
+//! is then stopped on the server depends on the host, so treat this as best
+//! effort: a statement that changes data may still complete.
 
 // pg4 errors can be large, because they now include a breakdown of the PostgreSQL
 // error fields instead of just a string
 #![allow(clippy::result_large_err)]
 
+use crate::retry::RetryPolicy;
 use crate::wit_bindgen;
 use std::sync::Arc;
 
@@ -50,6 +102,7 @@ pub mod wit {
         world: "spin-sdk-pg",
         path: "wit",
         generate_all,
+        additional_derives: [PartialEq],
     });
 
     pub use spin::postgres::postgres;
@@ -64,6 +117,13 @@ pub use wit::postgres::{
 /// The PostgreSQL INTERVAL data type.
 pub use wit::postgres::Interval;
 
+/// Derive macro generating an implementation of the [`FromRow`] trait.
+pub use spin_macro::FromRow;
+
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+pub use crate::db_json::JsonOptions;
+
 use chrono::{Datelike, Timelike};
 
 /// An open connection to a PostgreSQL database.
@@ -128,12 +188,26 @@ use chrono::{Datelike, Timelike};
 ///
 /// let rows_affected = db.execute(
 ///     "DELETE FROM users WHERE name = $1",
-///     &["Baldrick".to_owned().into()]
+///     &["Baldrick".into()]
 /// ).await?;
 /// # Ok(())
 /// # }
 /// ```
-pub struct Connection(wit::postgres::Connection);
+pub struct Connection {
+    inner: wit::postgres::Connection,
+    address: String,
+}
+
+/// Shows the address of the database with any credentials removed, such as
+/// `Connection { address: "host=localhost dbname=mydb" }`, so that connections can be
+/// logged safely.
+impl std::fmt::Debug for Connection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Connection")
+            .field("address", &self.address)
+            .finish()
+    }
+}
 
 /// Options for opening a [`Connection`].
 #[derive(Default)]
@@ -170,8 +244,13 @@ impl Connection {
     /// This constructor does not support options such as custom CA roots.
     /// See [`Connection::open_with_options`] for a more flexible constructor.
     pub async fn open(address: impl Into<String>) -> Result<Self, Error> {
-        let inner = wit::postgres::Connection::open_async(address.into()).await?;
-        Ok(Self(inner))
+        let address = address.into();
+        let redacted = crate::redact::redact_address(&address);
+        let inner = wit::postgres::Connection::open_async(address).await?;
+        Ok(Self {
+            inner,
+            address: redacted,
+        })
     }
 
     /// Open a connection to a PostgreSQL database.
@@ -193,7 +272,29 @@ impl Connection {
         }
 
         let inner = builder.build_async().await?;
-        Ok(Self(inner))
+        Ok(Self {
+            inner,
+            address: crate::redact::redact_address(address.as_ref()),
+        })
+    }
+
+    /// Open a connection to a PostgreSQL database, retrying if the connection
+    /// cannot be established.
+    ///
+    /// Only connection failures are retried; other errors, such as a malformed
+    /// address, are returned immediately. If all attempts fail, the error from
+    /// the last attempt is returned.
+    pub async fn open_with_retry(
+        address: impl AsRef<str>,
+        policy: RetryPolicy,
+    ) -> Result<Self, Error> {
+        let address = address.as_ref();
+        crate::retry::retry(
+            &policy,
+            || Self::open(address),
+            |e| matches!(e, Error::PgError(PgError::ConnectionFailed(_))),
+        )
+        .await
     }
 
     /// Query the database.
@@ -205,7 +306,10 @@ impl Connection {
         statement: impl Into<String>,
         params: impl Into<Vec<ParameterValue>>,
     ) -> Result<QueryResult, Error> {
-        let (columns, rows, result) = self.0.query_async(statement.into(), params.into()).await?;
+        let (columns, rows, result) = self
+            .inner
+            .query_async(statement.into(), params.into())
+            .await?;
         Ok(QueryResult {
             columns: Arc::new(columns),
             rows,
@@ -213,24 +317,192 @@ impl Connection {
         })
     }
 
+    /// Query the database, returning the rows as a [`Stream`](futures::Stream).
+    ///
+    /// This is [`Connection::query`] followed by [`QueryResult::into_stream`]:
+    /// rows are read from the host one at a time as the stream is polled, and
+    /// an error that ends the query part way is yielded as the last item.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use futures::TryStreamExt;
+    /// use spin_sdk::pg::{Connection, Decode};
+    ///
+    /// # async fn run() -> anyhow::Result<()> {
+    /// let db = Connection::open("host=localhost dbname=mydb").await?;
+    /// let mut rows = std::pin::pin!(db.query_stream("SELECT name FROM users", &[]).await?);
+    /// while let Some(row) = rows.try_next().await? {
+    ///     println!("{}", String::decode(&row[0])?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn query_stream(
+        &self,
+        statement: impl Into<String>,
+        params: impl Into<Vec<ParameterValue>>,
+    ) -> Result<impl futures::Stream<Item = Result<Row, Error>>, Error> {
+        Ok(self.query(statement, params).await?.into_stream())
+    }
+
     /// Execute a command against the database.
     ///
     /// Use this function for side-effectful queries (such as `INSERT` or `DELETE` queries).
     /// For queries that return row data, see [`Connection::query`].
+    ///
+    /// The host returns only the number of rows affected. Server notices and
+    /// warnings, such as those raised with `RAISE NOTICE` in a trigger, are
+    /// not passed on to the component. To get diagnostics from a function or
+    /// trigger, return them as query results or record them in a table.
     pub async fn execute(
         &self,
         statement: impl Into<String>,
         params: impl Into<Vec<ParameterValue>>,
     ) -> Result<u64, Error> {
-        self.0
+        self.inner
             .execute_async(statement.into(), params.into())
             .await
             .map_err(Error::PgError)
     }
 
+    /// Execute the same command once for each set of parameters.
+    ///
+    /// This is useful for repeated `INSERT` or `UPDATE` statements. The number
+    /// of rows affected by each execution is returned, in the order of `params`.
+    ///
+    /// The executions run inside a single transaction: if any of them fails,
+    /// the transaction is rolled back, so none of the changes are kept, and the
+    /// error is returned. Because this issues its own `BEGIN` and `COMMIT`, it
+    /// should not be called while a transaction is already open on the connection.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use spin_sdk::pg::Connection;
+    ///
+    /// # async fn run() -> anyhow::Result<()> {
+    /// let db = Connection::open("host=localhost dbname=mydb").await?;
+    /// let counts = db
+    ///     .execute_many(
+    ///         "INSERT INTO users (name) VALUES ($1)",
+    ///         [["Alice".into()], ["Bob".into()]],
+    ///     )
+    ///     .await?;
+    /// assert_eq!(counts, [1, 1]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn execute_many<P: Into<Vec<ParameterValue>>>(
+        &self,
+        statement: impl Into<String>,
+        params: impl IntoIterator<Item = P>,
+    ) -> Result<Vec<u64>, Error> {
+        let statement = statement.into();
+        self.execute("BEGIN", Vec::new()).await?;
+        let mut counts = Vec::new();
+        for params in params {
+            match self.execute(statement.clone(), params).await {
+                Ok(count) => counts.push(count),
+                Err(e) => {
+                    // The original error is more useful than any rollback failure.
+                    _ = self.execute("ROLLBACK", Vec::new()).await;
+                    return Err(e);
+                }
+            }
+        }
+        self.execute("COMMIT", Vec::new()).await?;
+        Ok(counts)
+    }
+
+    /// Query the database for exactly one row.
+    ///
+    /// Returns [`Error::UnexpectedRowCount`] if the query returns no rows or
+    /// more than one row. For a row that may not exist, see
+    /// [`Connection::query_opt`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use spin_sdk::pg::Connection;
+    ///
+    /// # async fn run() -> anyhow::Result<()> {
+    /// # let user_id = 0;
+    /// let db = Connection::open("host=localhost dbname=mydb").await?;
+    /// let user = db
+    ///     .query_one("SELECT * FROM users WHERE id = $1", &[user_id.into()])
+    ///     .await?;
+    /// let name = user.get::<String>("name");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn query_one(
+        &self,
+        statement: impl Into<String>,
+        params: impl Into<Vec<ParameterValue>>,
+    ) -> Result<Row, Error> {
+        self.query_opt(statement, params)
+            .await?
+            .ok_or(Error::UnexpectedRowCount(0))
+    }
+
+    /// Query the database for at most one row.
+    ///
+    /// Returns `None` if the query returns no rows, and
+    /// [`Error::UnexpectedRowCount`] if it returns more than one. Only the
+    /// first two rows are read, so a query that returns many rows fails
+    /// without fetching them all.
+    pub async fn query_opt(
+        &self,
+        statement: impl Into<String>,
+        params: impl Into<Vec<ParameterValue>>,
+    ) -> Result<Option<Row>, Error> {
+        let mut rows = self.query(statement, params).await?;
+        let Some(row) = rows.next().await else {
+            rows.result().await?;
+            return Ok(None);
+        };
+        if rows.next().await.is_some() {
+            // Dropping the result abandons the remaining rows.
+            return Err(Error::UnexpectedRowCount(2));
+        }
+        rows.result().await?;
+        Ok(Some(row))
+    }
+
+    /// Query the database for a single value, such as the result of
+    /// `SELECT COUNT(*)`.
+    ///
+    /// The query must return exactly one row, and the value in its first
+    /// column is decoded as `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use spin_sdk::pg::Connection;
+    ///
+    /// # async fn run() -> anyhow::Result<()> {
+    /// let db = Connection::open("host=localhost dbname=mydb").await?;
+    /// let count = db.query_scalar::<i64>("SELECT COUNT(*) FROM users", &[]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn query_scalar<T: Decode>(
+        &self,
+        statement: impl Into<String>,
+        params: impl Into<Vec<ParameterValue>>,
+    ) -> Result<T, Error> {
+        let row = self.query_one(statement, params).await?;
+        let value = row
+            .result
+            .first()
+            .ok_or_else(|| Error::Decode("query returned no columns".to_owned()))?;
+        T::decode(value)
+    }
+
     /// Extracts the underlying Wasm Component Model resource for the connection.
     pub fn into_inner(self) -> wit::postgres::Connection {
-        self.0
+        self.inner
     }
 }
 
@@ -272,6 +544,12 @@ impl QueryResult {
     ///
     /// This is provided for when the result set is small enough to fit in
     /// memory and you do not require streaming behaviour.
+    ///
+    /// Rows are streamed from the host as they are read, so a large result
+    /// set is never marshalled in one piece; the limit is the memory of the
+    /// component. For results that may be large, process rows one at a time
+    /// with [`QueryResult::next`], or paginate the query with `LIMIT` and a
+    /// keyset condition such as `WHERE id > $1 ORDER BY id`.
     pub async fn collect(mut self) -> Result<Vec<Row>, Error> {
         let mut rows = vec![];
         while let Some(row) = self.next().await {
@@ -281,6 +559,90 @@ impl QueryResult {
         Ok(rows)
     }
 
+    /// Collect all rows in the result set, converting each to `T` with
+    /// [`FromRow`].
+    ///
+    /// Like [`QueryResult::collect`], this is only suitable for result sets
+    /// small enough to fit in memory.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use spin_sdk::pg::{Connection, FromRow};
+    ///
+    /// #[derive(FromRow)]
+    /// struct User {
+    ///     id: i32,
+    ///     name: String,
+    ///     age: Option<i16>,
+    /// }
+    ///
+    /// # async fn run() -> anyhow::Result<()> {
+    /// let db = Connection::open("host=localhost dbname=mydb").await?;
+    /// let users: Vec<User> = db
+    ///     .query("SELECT id, name, age FROM users", &[])
+    ///     .await?
+    ///     .into_typed()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn into_typed<T: FromRow>(self) -> Result<Vec<T>, Error> {
+        self.collect().await?.iter().map(T::from_row).collect()
+    }
+
+    /// Convert the result into a [`Stream`](futures::Stream) of rows.
+    ///
+    /// Each row is read from the host when the stream is polled, and is
+    /// freed once the caller drops it, so only the rows the caller keeps are
+    /// held in memory. Once all rows have been read, the result of the query
+    /// is checked: if it failed, the stream ends with that error, so there is
+    /// no need to call [`QueryResult::result`] separately.
+    pub fn into_stream(self) -> impl futures::Stream<Item = Result<Row, Error>> {
+        let Self {
+            columns,
+            rows,
+            result,
+        } = self;
+        let rows = futures::stream::unfold(rows, move |mut rows| {
+            let columns = columns.clone();
+            async move {
+                let result = rows.next().await?;
+                Some((Row { columns, result }, rows))
+            }
+        });
+        rows_then_result(rows, result.into_future())
+    }
+
+    /// Collect all rows in the result set as a JSON array of objects keyed
+    /// by column name.
+    ///
+    /// Values are converted as described in [`to_json_value`]. Like
+    /// [`QueryResult::collect`], this is only suitable for result sets small
+    /// enough to fit in memory.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use spin_sdk::pg::{Connection, JsonOptions};
+    ///
+    /// # async fn run() -> anyhow::Result<()> {
+    /// let db = Connection::open("host=localhost dbname=mydb").await?;
+    /// let users = db
+    ///     .query("SELECT * FROM users", &[])
+    ///     .await?
+    ///     .collect_json(&JsonOptions::default())
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub async fn collect_json(self, options: &JsonOptions) -> Result<serde_json::Value, Error> {
+        let rows = self.collect().await?;
+        Ok(rows.iter().map(|row| row.to_json(options)).collect())
+    }
+
     /// An asynchronous reader for the rows of the query result. Call
     /// `.next().await` to iterate over the rows. When this returns `None`,
     /// you have read all available rows. At this point you _must_ check
@@ -361,6 +723,41 @@ impl Row {
         let db_value = self.result.get(i)?;
         Decode::decode(db_value).ok()
     }
+
+    /// Get a value by its column name, like [`Row::get`], but report why the
+    /// value could not be read.
+    ///
+    /// A missing column, or a value that cannot be converted to `T`, results
+    /// in an [`Error::Decode`] naming the column.
+    pub fn try_get<T: Decode>(&self, column: &str) -> Result<T, Error> {
+        let db_value = self
+            .columns
+            .iter()
+            .position(|c| c.name == column)
+            .and_then(|i| self.result.get(i))
+            .ok_or_else(|| Error::Decode(format!("no column named `{column}`")))?;
+        T::decode(db_value).map_err(|e| match e {
+            Error::Decode(msg) => Error::Decode(format!("column `{column}`: {msg}")),
+            e => e,
+        })
+    }
+}
+
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+impl Row {
+    /// Convert the row to a JSON object keyed by column name.
+    ///
+    /// Values are converted with [`to_json_value_with`]. If several columns
+    /// have the same name, the last one wins.
+    pub fn to_json(&self, options: &JsonOptions) -> serde_json::Value {
+        self.columns
+            .iter()
+            .zip(&self.result)
+            .map(|(column, value)| (column.name.clone(), to_json_value_with(value, options)))
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    }
 }
 
 impl std::ops::Index<usize> for Row {
@@ -371,12 +768,37 @@ impl std::ops::Index<usize> for Row {
     }
 }
 
+/// Yields each of `rows`, followed by the error from `result` if the query
+/// failed.
+fn rows_then_result<S, F>(rows: S, result: F) -> impl futures::Stream<Item = Result<Row, Error>>
+where
+    S: futures::Stream<Item = Row>,
+    F: Future<Output = Result<(), PgError>>,
+{
+    use futures::StreamExt;
+
+    let error = futures::stream::once(result)
+        .filter_map(|result| async move { result.err().map(|e| Err(Error::PgError(e))) });
+    rows.map(Ok).chain(error)
+}
+
 /// A Postgres error
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum Error {
     /// Failed to deserialize [`DbValue`]
     #[error("error value decoding: {0}")]
     Decode(String),
+    /// A query expected to return a single row returned none, or more than
+    /// one
+    ///
+    /// The count is 0 for no rows. As rows after the second are not read, it
+    /// is 2 for any larger number of rows.
+    #[error(
+        "expected a single row, but the query returned {}",
+        if *.0 == 0 { "none" } else { "more than one" }
+    )]
+    UnexpectedRowCount(usize),
     /// Postgres query failed with an error
     #[error(transparent)]
     PgError(#[from] PgError),
@@ -388,6 +810,34 @@ pub trait Decode: Sized {
     fn decode(value: &DbValue) -> Result<Self, Error>;
 }
 
+/// A type that can be built from a [`Row`].
+///
+/// This is usually derived with `#[derive(FromRow)]`, which reads each field
+/// of a struct from the column with the same name using [`Row::try_get`].
+/// Use [`QueryResult::into_typed`] to convert a whole result set.
+///
+/// # Examples
+///
+/// ```no_run
+/// use spin_sdk::pg::{Connection, FromRow};
+///
+/// #[derive(FromRow)]
+/// struct Cat {
+///     name: String,
+/// }
+///
+/// # async fn run() -> anyhow::Result<()> {
+/// let db = Connection::open("host=localhost dbname=mydb").await?;
+/// let row = db.query_one("SELECT name FROM cats LIMIT 1", &[]).await?;
+/// let cat = Cat::from_row(&row)?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait FromRow: Sized {
+    /// Build a value of this type from the columns of `row`.
+    fn from_row(row: &Row) -> Result<Self, Error>;
+}
+
 impl<T> Decode for Option<T>
 where
     T: Decode,
@@ -463,6 +913,18 @@ impl Decode for Vec<u8> {
     }
 }
 
+impl<const N: usize> Decode for [u8; N] {
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        match value {
+            DbValue::Binary(n) => n
+                .as_slice()
+                .try_into()
+                .map_err(|_| Error::Decode(format!("Expected {N} bytes but got {}", n.len()))),
+            _ => Err(Error::Decode(format_decode_err("BYTEA", value))),
+        }
+    }
+}
+
 impl Decode for String {
     fn decode(value: &DbValue) -> Result<Self, Error> {
         match value {
@@ -558,6 +1020,73 @@ impl Decode for chrono::Duration {
     }
 }
 
+#[cfg(feature = "pg-time")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pg-time")))]
+impl Decode for time::Date {
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        match value {
+            DbValue::Date((year, month, day)) => time_date(*year, *month, *day),
+            _ => Err(Error::Decode(format_decode_err("DATE", value))),
+        }
+    }
+}
+
+#[cfg(feature = "pg-time")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pg-time")))]
+impl Decode for time::Time {
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        match value {
+            DbValue::Time((hour, minute, second, nanosecond)) => {
+                time_time(*hour, *minute, *second, *nanosecond)
+            }
+            _ => Err(Error::Decode(format_decode_err("TIME", value))),
+        }
+    }
+}
+
+#[cfg(feature = "pg-time")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pg-time")))]
+impl Decode for time::PrimitiveDateTime {
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        match value {
+            DbValue::Datetime((year, month, day, hour, minute, second, nanosecond)) => {
+                Ok(time::PrimitiveDateTime::new(
+                    time_date(*year, *month, *day)?,
+                    time_time(*hour, *minute, *second, *nanosecond)?,
+                ))
+            }
+            _ => Err(Error::Decode(format_decode_err("DATETIME", value))),
+        }
+    }
+}
+
+/// Postgres sends TIMESTAMPTZ values in UTC, so they are decoded with a
+/// zero offset.
+#[cfg(feature = "pg-time")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pg-time")))]
+impl Decode for time::OffsetDateTime {
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        time::PrimitiveDateTime::decode(value).map(time::PrimitiveDateTime::assume_utc)
+    }
+}
+
+#[cfg(feature = "pg-time")]
+fn time_date(year: i32, month: u8, day: u8) -> Result<time::Date, Error> {
+    time::Month::try_from(month)
+        .and_then(|m| time::Date::from_calendar_date(year, m, day))
+        .map_err(|_| Error::Decode(format!("invalid date y={}, m={}, d={}", year, month, day)))
+}
+
+#[cfg(feature = "pg-time")]
+fn time_time(hour: u8, minute: u8, second: u8, nanosecond: u32) -> Result<time::Time, Error> {
+    time::Time::from_hms_nano(hour, minute, second, nanosecond).map_err(|_| {
+        Error::Decode(format!(
+            "invalid time {}:{}:{}:{}",
+            hour, minute, second, nanosecond
+        ))
+    })
+}
+
 #[cfg(feature = "postgres4-types")]
 impl Decode for uuid::Uuid {
     fn decode(value: &DbValue) -> Result<Self, Error> {
@@ -596,60 +1125,541 @@ impl Decode for rust_decimal::Decimal {
     }
 }
 
-#[cfg(feature = "postgres4-types")]
-fn bound_type_from_wit(kind: RangeBoundKind) -> postgres_range::BoundType {
-    match kind {
-        RangeBoundKind::Inclusive => postgres_range::BoundType::Inclusive,
-        RangeBoundKind::Exclusive => postgres_range::BoundType::Exclusive,
+/// Convert a Postgres NUMERIC value to an `f64`.
+///
+/// **This conversion is lossy.** NUMERIC values can have far more precision
+/// than an `f64` can represent, and values outside the `f64` range become
+/// infinite. Use this only where approximate results are acceptable; for
+/// exact values, decode to `rust_decimal::Decimal` instead.
+///
+/// This is deliberately not a [`Decode`] implementation for `f64`, so that
+/// precision loss is always an explicit choice.
+pub fn decode_numeric_as_f64(value: &DbValue) -> Result<f64, Error> {
+    match value {
+        DbValue::Decimal(s) => s
+            .parse::<f64>()
+            .map_err(|e| Error::Decode(format!("invalid NUMERIC value {s:?}: {e}"))),
+        _ => Err(Error::Decode(format_decode_err("NUMERIC", value))),
     }
 }
 
-#[cfg(feature = "postgres4-types")]
-impl Decode for postgres_range::Range<i32> {
-    fn decode(value: &DbValue) -> Result<Self, Error> {
-        match value {
-            DbValue::RangeInt32((lbound, ubound)) => {
-                let lower = lbound.map(|(value, kind)| {
-                    postgres_range::RangeBound::new(value, bound_type_from_wit(kind))
-                });
-                let upper = ubound.map(|(value, kind)| {
-                    postgres_range::RangeBound::new(value, bound_type_from_wit(kind))
-                });
-                Ok(postgres_range::Range::new(lower, upper))
-            }
-            _ => Err(Error::Decode(format_decode_err("INT4RANGE", value))),
+/// Parse the text form of a Postgres BYTEA value.
+///
+/// Both output formats are accepted: the hex format (`\x` followed by pairs
+/// of hex digits, the default since Postgres 9.0) and the older escape
+/// format, in which a backslash introduces either another backslash or three
+/// octal digits, and other characters stand for themselves.
+///
+/// This is used to read BYTEA arrays, which arrive as arrays of text, and is
+/// also useful for BYTEA values selected as text.
+pub fn decode_bytea(text: &str) -> Result<Vec<u8>, Error> {
+    let invalid = || Error::Decode(format!("invalid BYTEA value {text:?}"));
+    if let Some(hex) = text.strip_prefix("\\x") {
+        let digits = hex
+            .bytes()
+            .filter(|b| !b.is_ascii_whitespace())
+            .map(|b| (b as char).to_digit(16).ok_or_else(invalid))
+            .collect::<Result<Vec<_>, _>>()?;
+        if digits.len() % 2 != 0 {
+            return Err(invalid());
+        }
+        return Ok(digits
+            .chunks(2)
+            .map(|pair| (pair[0] * 16 + pair[1]) as u8)
+            .collect());
+    }
+
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        if b != b'\\' {
+            bytes.push(b);
+            rest = tail;
+        } else if let Some(tail) = tail.strip_prefix(b"\\") {
+            bytes.push(b'\\');
+            rest = tail;
+        } else {
+            let octal = tail.get(..3).ok_or_else(invalid)?;
+            let octal = std::str::from_utf8(octal).map_err(|_| invalid())?;
+            bytes.push(u8::from_str_radix(octal, 8).map_err(|_| invalid())?);
+            rest = &tail[3..];
         }
     }
+    Ok(bytes)
 }
 
-#[cfg(feature = "postgres4-types")]
-impl Decode for postgres_range::Range<i64> {
-    fn decode(value: &DbValue) -> Result<Self, Error> {
-        match value {
-            DbValue::RangeInt64((lbound, ubound)) => {
-                let lower = lbound.map(|(value, kind)| {
-                    postgres_range::RangeBound::new(value, bound_type_from_wit(kind))
-                });
-                let upper = ubound.map(|(value, kind)| {
-                    postgres_range::RangeBound::new(value, bound_type_from_wit(kind))
-                });
-                Ok(postgres_range::Range::new(lower, upper))
-            }
-            _ => Err(Error::Decode(format_decode_err("INT8RANGE", value))),
-        }
-    }
+/// Convert a Postgres BIT or BIT VARYING value to its bits, first bit
+/// first.
+///
+/// These types have no direct mapping, so select the column as text (for
+/// example `SELECT flags::text`), which gives a string of `0`s and `1`s.
+/// To pass bits as a parameter, use [`encode_bits`].
+pub fn decode_bits(value: &DbValue) -> Result<Vec<bool>, Error> {
+    match value {
+        DbValue::Str(s) => s
+            .chars()
+            .map(|c| match c {
+                '0' => Ok(false),
+                '1' => Ok(true),
+                _ => Err(Error::Decode(format!("invalid BIT value {s:?}"))),
+            })
+            .collect(),
+        _ => Err(Error::Decode(format_decode_err(
+            "BIT, VARBIT (as TEXT)",
+            value,
+        ))),
+    }
+}
+
+/// Encode bits as a parameter for a BIT or BIT VARYING column, first bit
+/// first.
+///
+/// The bits are passed as text, so the statement must cast the parameter,
+/// for example `$1::text::varbit` or `$1::text::bit(8)`.
+pub fn encode_bits(bits: &[bool]) -> ParameterValue {
+    ParameterValue::Str(bits.iter().map(|&b| if b { '1' } else { '0' }).collect())
 }
 
-// We can't use postgres_range::Range because rust_decimal::Decimal
-// is not Normalizable
-#[cfg(feature = "postgres4-types")]
-impl Decode
-    for (
-        Option<(rust_decimal::Decimal, RangeBoundKind)>,
-        Option<(rust_decimal::Decimal, RangeBoundKind)>,
-    )
-{
-    fn decode(value: &DbValue) -> Result<Self, Error> {
+/// Split a Postgres composite (row) value into its fields.
+///
+/// Composite values have no direct mapping, so select them as text (for
+/// example `SELECT address::text` or `SELECT ROW(id, name)::text`), which
+/// gives the record syntax `(f1,f2,...)`. Each field is returned as a
+/// [`DbValue::Str`] holding its text form, or [`DbValue::DbNull`] if it is
+/// NULL, so it can be read with [`Decode`] for `String` or `Option<String>`
+/// and parsed from there. A nested composite is returned as text and can be
+/// split with another call.
+///
+/// # Examples
+///
+/// ```no_run
+/// use spin_sdk::pg::{Decode, DbValue, Error, decode_record};
+///
+/// struct Address {
+///     street: String,
+///     zip: Option<u32>,
+/// }
+///
+/// fn decode_address(value: &DbValue) -> Result<Address, Error> {
+///     let [street, zip] = decode_record(value)?
+///         .try_into()
+///         .map_err(|_| Error::Decode("expected 2 address fields".into()))?;
+///     Ok(Address {
+///         street: String::decode(&street)?,
+///         zip: Option::<String>::decode(&zip)?
+///             .map(|zip| zip.parse())
+///             .transpose()
+///             .map_err(|e| Error::Decode(format!("invalid zip: {e}")))?,
+///     })
+/// }
+/// ```
+pub fn decode_record(value: &DbValue) -> Result<Vec<DbValue>, Error> {
+    let DbValue::Str(text) = value else {
+        return Err(Error::Decode(format_decode_err(
+            "composite type (as TEXT)",
+            value,
+        )));
+    };
+    let invalid = || Error::Decode(format!("invalid record value {text:?}"));
+    let inner = text
+        .strip_prefix('(')
+        .and_then(|s| s.strip_suffix(')'))
+        .ok_or_else(invalid)?;
+
+    let fields = split_text_fields(inner).ok_or_else(invalid)?;
+    Ok(fields
+        .into_iter()
+        .map(|field| field.map_or(DbValue::DbNull, DbValue::Str))
+        .collect())
+}
+
+/// Split the comma-separated fields of a record or range in text form,
+/// without its enclosing brackets.
+///
+/// A field may be quoted, with `""` or `\"` for a quote and `\\` for a
+/// backslash. An empty unquoted field is returned as `None`, while `""` is
+/// an empty string. Returns `None` if a quote or escape is unterminated.
+fn split_text_fields(inner: &str) -> Option<Vec<Option<String>>> {
+    let mut fields = Vec::new();
+    let mut chars = inner.chars().peekable();
+    loop {
+        let mut field = String::new();
+        let (mut quoted, mut in_quotes) = (false, false);
+        let more = loop {
+            match chars.next() {
+                None if in_quotes => return None,
+                None => break false,
+                Some(',') if !in_quotes => break true,
+                Some('"') if in_quotes && chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                Some('"') => {
+                    quoted = true;
+                    in_quotes = !in_quotes;
+                }
+                Some('\\') => field.push(chars.next()?),
+                Some(c) => field.push(c),
+            }
+        };
+        fields.push((quoted || !field.is_empty()).then_some(field));
+        if !more {
+            return Some(fields);
+        }
+    }
+}
+
+/// Convert a Postgres MONEY value to a `rust_decimal::Decimal`.
+///
+/// MONEY has no direct mapping, so select the column as text (for example
+/// `SELECT price::text`) or as NUMERIC (`price::numeric`). NUMERIC values are
+/// decoded exactly. Text values are formatted according to the database's
+/// `lc_monetary` setting, such as `$1,234.56`, `-$1,234.56` or `1.234,56 €`.
+/// Currency symbols and digit grouping are stripped, and a leading `-` or
+/// surrounding parentheses mark a negative amount.
+///
+/// The decimal separator is taken to be the last `.` or `,` in the text,
+/// unless that character appears more than once, or is the only separator
+/// and is followed by exactly three digits, in which case it is treated as
+/// digit grouping. This matches the common locales, but for currencies with
+/// three fractional digits, prefer selecting the value as NUMERIC.
+#[cfg(feature = "postgres4-types")]
+#[cfg_attr(docsrs, doc(cfg(feature = "postgres4-types")))]
+pub fn decode_money(value: &DbValue) -> Result<rust_decimal::Decimal, Error> {
+    match value {
+        DbValue::Decimal(s) => {
+            rust_decimal::Decimal::from_str_exact(s).map_err(|e| Error::Decode(e.to_string()))
+        }
+        DbValue::Str(s) => {
+            parse_money(s).ok_or_else(|| Error::Decode(format!("invalid MONEY value {s:?}")))
+        }
+        _ => Err(Error::Decode(format_decode_err(
+            "MONEY (as TEXT or NUMERIC)",
+            value,
+        ))),
+    }
+}
+
+#[cfg(feature = "postgres4-types")]
+fn parse_money(s: &str) -> Option<rust_decimal::Decimal> {
+    let s = s.trim();
+    let negative = s.contains('-') || (s.starts_with('(') && s.ends_with(')'));
+    let kept: String = s
+        .chars()
+        .filter(|c| c.is_ascii_digit() || matches!(c, '.' | ','))
+        .collect();
+
+    let decimal_separator = kept.rfind(['.', ',']).filter(|&i| {
+        let separator = kept.as_bytes()[i] as char;
+        let occurrences = kept.matches(separator).count();
+        let other_present = kept.contains(if separator == '.' { ',' } else { '.' });
+        let fraction_len = kept.len() - i - 1;
+        occurrences == 1 && (other_present || fraction_len != 3)
+    });
+
+    let (whole, fraction) = match decimal_separator {
+        Some(i) => (&kept[..i], &kept[i + 1..]),
+        None => (kept.as_str(), ""),
+    };
+    let whole: String = whole.chars().filter(char::is_ascii_digit).collect();
+    if whole.is_empty() && fraction.is_empty() {
+        return None;
+    }
+    let normalized = if fraction.is_empty() {
+        whole
+    } else {
+        format!("{whole}.{fraction}")
+    };
+    let amount = rust_decimal::Decimal::from_str_exact(&normalized).ok()?;
+    Some(if negative { -amount } else { amount })
+}
+
+/// Formats the value as described in [`display_value`].
+impl std::fmt::Display for DbValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&display_value(self))
+    }
+}
+
+/// Render any [`DbValue`] as a human-readable string.
+///
+/// This is intended for generic tooling, such as query consoles or admin
+/// pages, that needs to show values without knowing their types in advance.
+/// The output follows PostgreSQL's text representation where practical: for
+/// example, byte arrays are shown as `\x` followed by hex digits, ranges as
+/// `[1,10)`, and arrays as `{1,2,NULL}`. SQL `NULL` is shown as `NULL`.
+///
+/// To decode a value to a specific Rust type, use [`Decode`] instead.
+pub fn display_value(value: &DbValue) -> String {
+    match value {
+        DbValue::Boolean(b) => b.to_string(),
+        DbValue::Int8(n) => n.to_string(),
+        DbValue::Int16(n) => n.to_string(),
+        DbValue::Int32(n) => n.to_string(),
+        DbValue::Int64(n) => n.to_string(),
+        DbValue::Floating32(n) => n.to_string(),
+        DbValue::Floating64(n) => n.to_string(),
+        DbValue::Str(s) | DbValue::Uuid(s) | DbValue::Decimal(s) => s.clone(),
+        DbValue::Binary(b) => display_bytes(b),
+        DbValue::Date((year, month, day)) => format!("{year:04}-{month:02}-{day:02}"),
+        DbValue::Time((hour, minute, second, nanosecond)) => {
+            display_time(*hour, *minute, *second, *nanosecond)
+        }
+        DbValue::Datetime((year, month, day, hour, minute, second, nanosecond)) => format!(
+            "{year:04}-{month:02}-{day:02} {}",
+            display_time(*hour, *minute, *second, *nanosecond)
+        ),
+        DbValue::Timestamp(n) => n.to_string(),
+        DbValue::Jsonb(j) => String::from_utf8_lossy(j).into_owned(),
+        DbValue::RangeInt32((lower, upper)) => display_range(lower, upper),
+        DbValue::RangeInt64((lower, upper)) => display_range(lower, upper),
+        DbValue::RangeDecimal((lower, upper)) => display_range(lower, upper),
+        DbValue::ArrayInt32(a) => display_array(a.iter().map(|v| v.map(|n| n.to_string()))),
+        DbValue::ArrayInt64(a) => display_array(a.iter().map(|v| v.map(|n| n.to_string()))),
+        DbValue::ArrayDecimal(a) => display_array(a.iter().cloned()),
+        DbValue::ArrayStr(a) => {
+            display_array(a.iter().map(|v| v.as_deref().map(quote_array_element)))
+        }
+        DbValue::Interval(i) => display_interval(i),
+        DbValue::DbNull => "NULL".to_owned(),
+        DbValue::Unsupported(_) => "<unsupported>".to_owned(),
+    }
+}
+
+/// Convert any [`DbValue`] to a [`serde_json::Value`].
+///
+/// Like [`display_value`], this is intended for generic tooling such as
+/// APIs that return arbitrary result sets. Numbers and booleans become JSON
+/// numbers and booleans, JSONB values are embedded as JSON, and SQL `NULL`
+/// becomes `null`. NUMERIC values are rendered as strings so that no precision
+/// is lost, byte arrays become hex strings prefixed with `0x`, non-finite
+/// floats become `null`, and dates and times use their [`display_value`]
+/// text. Arrays become JSON arrays, ranges become objects with `lower`, `upper`, `lower_inclusive`
+/// and `upper_inclusive` fields, and intervals become objects with `months`,
+/// `days` and `micros` fields. Unsupported values become `null`.
+///
+/// To render NUMERIC values as numbers, use [`to_json_value_with`].
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+pub fn to_json_value(value: &DbValue) -> serde_json::Value {
+    to_json_value_with(value, &JsonOptions::default())
+}
+
+/// Convert any [`DbValue`] to a [`serde_json::Value`], as [`to_json_value`]
+/// does, using the given options.
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+pub fn to_json_value_with(value: &DbValue, options: &JsonOptions) -> serde_json::Value {
+    use crate::db_json::Scalar;
+    use serde_json::{Value, json};
+
+    fn range<T>(
+        lower: &Option<(T, RangeBoundKind)>,
+        upper: &Option<(T, RangeBoundKind)>,
+        convert: impl Fn(&T) -> Value,
+    ) -> Value {
+        let value = |bound: &Option<(T, RangeBoundKind)>| match bound {
+            Some((v, _)) => convert(v),
+            None => Value::Null,
+        };
+        let inclusive = |bound: &Option<(T, RangeBoundKind)>| {
+            matches!(bound, Some((_, RangeBoundKind::Inclusive)))
+        };
+        json!({
+            "lower": value(lower),
+            "upper": value(upper),
+            "lower_inclusive": inclusive(lower),
+            "upper_inclusive": inclusive(upper),
+        })
+    }
+
+    let numeric = |s: &String| options.convert(Scalar::Numeric(s));
+
+    match value {
+        DbValue::Boolean(b) => options.convert(Scalar::Bool(*b)),
+        DbValue::Int8(n) => options.convert(Scalar::Int((*n).into())),
+        DbValue::Int16(n) => options.convert(Scalar::Int((*n).into())),
+        DbValue::Int32(n) => options.convert(Scalar::Int((*n).into())),
+        DbValue::Int64(n) | DbValue::Timestamp(n) => options.convert(Scalar::Int(*n)),
+        DbValue::Floating32(n) => options.convert(Scalar::Float((*n).into())),
+        DbValue::Floating64(n) => options.convert(Scalar::Float(*n)),
+        DbValue::Str(s) | DbValue::Uuid(s) => options.convert(Scalar::Text(s)),
+        DbValue::Decimal(s) => numeric(s),
+        DbValue::Binary(b) => options.convert(Scalar::Bytes(b)),
+        DbValue::Date(_) | DbValue::Time(_) | DbValue::Datetime(_) => {
+            Value::String(display_value(value))
+        }
+        DbValue::Jsonb(j) => serde_json::from_slice(j)
+            .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(j).into_owned())),
+        DbValue::RangeInt32((lower, upper)) => range(lower, upper, |n| (*n).into()),
+        DbValue::RangeInt64((lower, upper)) => range(lower, upper, |n| (*n).into()),
+        DbValue::RangeDecimal((lower, upper)) => range(lower, upper, numeric),
+        DbValue::ArrayInt32(a) => a.iter().copied().collect(),
+        DbValue::ArrayInt64(a) => a.iter().copied().collect(),
+        DbValue::ArrayDecimal(a) => a
+            .iter()
+            .map(|v| v.as_ref().map_or(Value::Null, numeric))
+            .collect(),
+        DbValue::ArrayStr(a) => a.iter().cloned().collect(),
+        DbValue::Interval(i) => json!({
+            "months": i.months,
+            "days": i.days,
+            "micros": i.micros,
+        }),
+        DbValue::DbNull | DbValue::Unsupported(_) => options.convert(Scalar::Null),
+    }
+}
+
+fn display_bytes(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+
+    let mut s = String::with_capacity(2 + bytes.len() * 2);
+    s.push_str("\\x");
+    for b in bytes {
+        _ = write!(s, "{b:02x}");
+    }
+    s
+}
+
+fn display_time(hour: u8, minute: u8, second: u8, nanosecond: u32) -> String {
+    let mut s = format!("{hour:02}:{minute:02}:{second:02}");
+    if nanosecond > 0 {
+        let fraction = format!("{nanosecond:09}");
+        s.push('.');
+        s.push_str(fraction.trim_end_matches('0'));
+    }
+    s
+}
+
+fn display_range<T: std::fmt::Display>(
+    lower: &Option<(T, RangeBoundKind)>,
+    upper: &Option<(T, RangeBoundKind)>,
+) -> String {
+    let mut s = String::new();
+    match lower {
+        Some((v, RangeBoundKind::Inclusive)) => s.push_str(&format!("[{v}")),
+        Some((v, RangeBoundKind::Exclusive)) => s.push_str(&format!("({v}")),
+        None => s.push('('),
+    }
+    s.push(',');
+    match upper {
+        Some((v, RangeBoundKind::Inclusive)) => s.push_str(&format!("{v}]")),
+        Some((v, RangeBoundKind::Exclusive)) => s.push_str(&format!("{v})")),
+        None => s.push(')'),
+    }
+    s
+}
+
+fn display_array(elements: impl Iterator<Item = Option<String>>) -> String {
+    let elements: Vec<_> = elements
+        .map(|e| e.unwrap_or_else(|| "NULL".to_owned()))
+        .collect();
+    format!("{{{}}}", elements.join(","))
+}
+
+/// Quotes a text array element the way PostgreSQL does when it would
+/// otherwise be ambiguous.
+fn quote_array_element(s: &str) -> String {
+    let needs_quotes = s.is_empty()
+        || s.eq_ignore_ascii_case("NULL")
+        || s.chars()
+            .any(|c| matches!(c, ',' | '{' | '}' | '"' | '\\') || c.is_whitespace());
+    if !needs_quotes {
+        return s.to_owned();
+    }
+    let escaped = s.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{escaped}\"")
+}
+
+fn display_interval(interval: &Interval) -> String {
+    fn unit(n: i64, singular: &str, plural: &str) -> String {
+        format!("{n} {}", if n == 1 { singular } else { plural })
+    }
+
+    let mut parts = Vec::new();
+    let (years, months) = (interval.months / 12, interval.months % 12);
+    if years != 0 {
+        parts.push(unit(years.into(), "year", "years"));
+    }
+    if months != 0 {
+        parts.push(unit(months.into(), "mon", "mons"));
+    }
+    if interval.days != 0 {
+        parts.push(unit(interval.days.into(), "day", "days"));
+    }
+    if interval.micros != 0 || parts.is_empty() {
+        let sign = if interval.micros < 0 { "-" } else { "" };
+        let micros = interval.micros.unsigned_abs();
+        let seconds = micros / 1_000_000;
+        let mut time = format!(
+            "{sign}{:02}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        );
+        let fraction = micros % 1_000_000;
+        if fraction > 0 {
+            time.push_str(format!(".{fraction:06}").trim_end_matches('0'));
+        }
+        parts.push(time);
+    }
+    parts.join(" ")
+}
+
+#[cfg(feature = "postgres4-types")]
+fn bound_type_from_wit(kind: RangeBoundKind) -> postgres_range::BoundType {
+    match kind {
+        RangeBoundKind::Inclusive => postgres_range::BoundType::Inclusive,
+        RangeBoundKind::Exclusive => postgres_range::BoundType::Exclusive,
+    }
+}
+
+#[cfg(feature = "postgres4-types")]
+impl Decode for postgres_range::Range<i32> {
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        match value {
+            DbValue::RangeInt32((lbound, ubound)) => {
+                let lower = lbound.map(|(value, kind)| {
+                    postgres_range::RangeBound::new(value, bound_type_from_wit(kind))
+                });
+                let upper = ubound.map(|(value, kind)| {
+                    postgres_range::RangeBound::new(value, bound_type_from_wit(kind))
+                });
+                Ok(postgres_range::Range::new(lower, upper))
+            }
+            _ => Err(Error::Decode(format_decode_err("INT4RANGE", value))),
+        }
+    }
+}
+
+#[cfg(feature = "postgres4-types")]
+impl Decode for postgres_range::Range<i64> {
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        match value {
+            DbValue::RangeInt64((lbound, ubound)) => {
+                let lower = lbound.map(|(value, kind)| {
+                    postgres_range::RangeBound::new(value, bound_type_from_wit(kind))
+                });
+                let upper = ubound.map(|(value, kind)| {
+                    postgres_range::RangeBound::new(value, bound_type_from_wit(kind))
+                });
+                Ok(postgres_range::Range::new(lower, upper))
+            }
+            _ => Err(Error::Decode(format_decode_err("INT8RANGE", value))),
+        }
+    }
+}
+
+// We can't use postgres_range::Range because rust_decimal::Decimal
+// is not Normalizable
+#[cfg(feature = "postgres4-types")]
+impl Decode
+    for (
+        Option<(rust_decimal::Decimal, RangeBoundKind)>,
+        Option<(rust_decimal::Decimal, RangeBoundKind)>,
+    )
+{
+    fn decode(value: &DbValue) -> Result<Self, Error> {
         fn parse(
             value: &str,
             kind: RangeBoundKind,
@@ -676,6 +1686,104 @@ impl Decode
     }
 }
 
+/// TSRANGE values have no WIT range type, so select them as text (for
+/// example `SELECT slot::text`). An unbounded or infinite bound is `None`,
+/// and an empty range fails to decode, as it cannot be told apart from an
+/// unbounded one in this form.
+impl Decode
+    for (
+        Option<(chrono::NaiveDateTime, RangeBoundKind)>,
+        Option<(chrono::NaiveDateTime, RangeBoundKind)>,
+    )
+{
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        decode_text_range(value, "TSRANGE", |s| {
+            chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f").ok()
+        })
+    }
+}
+
+/// TSRANGE values have no WIT range type, so select them as text (for
+/// example `SELECT slot::text`). Unlike the tuple form, this can represent
+/// an empty range. An infinite bound is treated as unbounded.
+#[cfg(feature = "postgres4-types")]
+impl Decode for postgres_range::Range<chrono::NaiveDateTime> {
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        if matches!(value, DbValue::Str(s) if s == "empty") {
+            return Ok(postgres_range::Range::empty());
+        }
+        let (lower, upper) = TextRangeBounds::<chrono::NaiveDateTime>::decode(value)?;
+        let lower = lower
+            .map(|(value, kind)| postgres_range::RangeBound::new(value, bound_type_from_wit(kind)));
+        let upper = upper
+            .map(|(value, kind)| postgres_range::RangeBound::new(value, bound_type_from_wit(kind)));
+        Ok(postgres_range::Range::new(lower, upper))
+    }
+}
+
+/// DATERANGE values have no WIT range type, so select them as text (for
+/// example `SELECT stay::text`). Postgres normalizes date ranges, so the
+/// upper bound is exclusive unless it is unbounded. An unbounded or infinite
+/// bound is `None`, and an empty range fails to decode.
+impl Decode
+    for (
+        Option<(chrono::NaiveDate, RangeBoundKind)>,
+        Option<(chrono::NaiveDate, RangeBoundKind)>,
+    )
+{
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        decode_text_range(value, "DATERANGE", |s| {
+            chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()
+        })
+    }
+}
+
+type TextRangeBounds<T> = (Option<(T, RangeBoundKind)>, Option<(T, RangeBoundKind)>);
+
+fn decode_text_range<T>(
+    value: &DbValue,
+    type_name: &str,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Result<TextRangeBounds<T>, Error> {
+    let DbValue::Str(text) = value else {
+        return Err(Error::Decode(format_decode_err(
+            &format!("{type_name} (as TEXT)"),
+            value,
+        )));
+    };
+    let invalid = || Error::Decode(format!("invalid {type_name} value {text:?}"));
+    if text == "empty" {
+        return Err(Error::Decode(format!("{type_name} value is empty")));
+    }
+
+    let kind = |inclusive| {
+        if inclusive {
+            RangeBoundKind::Inclusive
+        } else {
+            RangeBoundKind::Exclusive
+        }
+    };
+    let lower_kind = match text.chars().next() {
+        Some('[') => kind(true),
+        Some('(') => kind(false),
+        _ => return Err(invalid()),
+    };
+    let upper_kind = match text.chars().last() {
+        Some(']') => kind(true),
+        Some(')') => kind(false),
+        _ => return Err(invalid()),
+    };
+    let inner = text.get(1..text.len() - 1).ok_or_else(invalid)?;
+    let [lower, upper]: [Option<String>; 2] = split_text_fields(inner)
+        .and_then(|fields| fields.try_into().ok())
+        .ok_or_else(invalid)?;
+    let bound = |field: Option<String>, kind| match field.as_deref() {
+        None | Some("infinity" | "-infinity") => Ok(None),
+        Some(s) => parse(s).map(|v| Some((v, kind))).ok_or_else(invalid),
+    };
+    Ok((bound(lower, lower_kind)?, bound(upper, upper_kind)?))
+}
+
 // TODO: can we return a slice here? It seems like it should be possible but
 // I wasn't able to get the lifetimes to work with the trait
 impl Decode for Vec<Option<i32>> {
@@ -705,6 +1813,98 @@ impl Decode for Vec<Option<String>> {
     }
 }
 
+impl Decode for Vec<Option<i16>> {
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        match value {
+            DbValue::ArrayInt32(a) => a
+                .iter()
+                .map(|n| {
+                    n.map(|n| {
+                        i16::try_from(n).map_err(|_| {
+                            Error::Decode(format!("array element {n} is out of range for INT2"))
+                        })
+                    })
+                    .transpose()
+                })
+                .collect(),
+            _ => Err(Error::Decode(format_decode_err(
+                "INT2[] (as INT4[])",
+                value,
+            ))),
+        }
+    }
+}
+
+fn map_float<T: std::str::FromStr>(s: &Option<String>) -> Result<Option<T>, Error> {
+    s.as_ref()
+        .map(|s| match s.as_str() {
+            "Infinity" => "inf".parse(),
+            "-Infinity" => "-inf".parse(),
+            s => s.parse(),
+        })
+        .transpose()
+        .map_err(|_| Error::Decode(format!("invalid floating point array element {s:?}")))
+}
+
+impl Decode for Vec<Option<f32>> {
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        match value {
+            DbValue::ArrayDecimal(a) => a.iter().map(map_float).collect(),
+            _ => Err(Error::Decode(format_decode_err(
+                "REAL[] (as NUMERIC[])",
+                value,
+            ))),
+        }
+    }
+}
+
+impl Decode for Vec<Option<f64>> {
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        match value {
+            DbValue::ArrayDecimal(a) => a.iter().map(map_float).collect(),
+            _ => Err(Error::Decode(format_decode_err(
+                "DOUBLE PRECISION[] (as NUMERIC[])",
+                value,
+            ))),
+        }
+    }
+}
+
+impl Decode for Vec<Option<Vec<u8>>> {
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        match value {
+            DbValue::ArrayStr(a) => a
+                .iter()
+                .map(|b| b.as_deref().map(decode_bytea).transpose())
+                .collect(),
+            _ => Err(Error::Decode(format_decode_err(
+                "BYTEA[] (as TEXT[])",
+                value,
+            ))),
+        }
+    }
+}
+
+impl Decode for Vec<Option<bool>> {
+    fn decode(value: &DbValue) -> Result<Self, Error> {
+        match value {
+            DbValue::ArrayStr(a) => a
+                .iter()
+                .map(|b| match b.as_deref() {
+                    None => Ok(None),
+                    Some("t" | "true") => Ok(Some(true)),
+                    Some("f" | "false") => Ok(Some(false)),
+                    Some(b) => Err(Error::Decode(format!("invalid BOOL array element {b:?}"))),
+                })
+                .collect(),
+            _ => Err(Error::Decode(format_decode_err(
+                "BOOL[] (as TEXT[])",
+                value,
+            ))),
+        }
+    }
+}
+
 #[cfg(feature = "postgres4-types")]
 fn map_decimal(s: &Option<String>) -> Result<Option<rust_decimal::Decimal>, Error> {
     s.as_ref()
@@ -747,6 +1947,24 @@ macro_rules! impl_parameter_value_conversions {
     };
 }
 
+impl From<&str> for ParameterValue {
+    fn from(v: &str) -> ParameterValue {
+        ParameterValue::Str(v.to_owned())
+    }
+}
+
+impl From<&[u8]> for ParameterValue {
+    fn from(v: &[u8]) -> ParameterValue {
+        ParameterValue::Binary(v.to_vec())
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for ParameterValue {
+    fn from(v: [u8; N]) -> ParameterValue {
+        ParameterValue::Binary(v.to_vec())
+    }
+}
+
 impl_parameter_value_conversions! {
     i8 => Int8,
     i16 => Int16,
@@ -799,6 +2017,49 @@ impl From<chrono::TimeDelta> for ParameterValue {
     }
 }
 
+#[cfg(feature = "pg-time")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pg-time")))]
+impl From<time::Date> for ParameterValue {
+    fn from(v: time::Date) -> ParameterValue {
+        ParameterValue::Date((v.year(), v.month().into(), v.day()))
+    }
+}
+
+#[cfg(feature = "pg-time")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pg-time")))]
+impl From<time::Time> for ParameterValue {
+    fn from(v: time::Time) -> ParameterValue {
+        ParameterValue::Time((v.hour(), v.minute(), v.second(), v.nanosecond()))
+    }
+}
+
+#[cfg(feature = "pg-time")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pg-time")))]
+impl From<time::PrimitiveDateTime> for ParameterValue {
+    fn from(v: time::PrimitiveDateTime) -> ParameterValue {
+        ParameterValue::Datetime((
+            v.year(),
+            v.month().into(),
+            v.day(),
+            v.hour(),
+            v.minute(),
+            v.second(),
+            v.nanosecond(),
+        ))
+    }
+}
+
+/// The value is converted to UTC, which is how Postgres compares and
+/// stores TIMESTAMPTZ values.
+#[cfg(feature = "pg-time")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pg-time")))]
+impl From<time::OffsetDateTime> for ParameterValue {
+    fn from(v: time::OffsetDateTime) -> ParameterValue {
+        let v = v.to_offset(time::UtcOffset::UTC);
+        time::PrimitiveDateTime::new(v.date(), v.time()).into()
+    }
+}
+
 #[cfg(feature = "postgres4-types")]
 impl From<uuid::Uuid> for ParameterValue {
     fn from(v: uuid::Uuid) -> ParameterValue {
@@ -953,6 +2214,55 @@ impl From<std::ops::Range<rust_decimal::Decimal>> for ParameterValue {
     }
 }
 
+/// The range is passed as text, so the statement must cast the parameter,
+/// for example `$1::text::tsrange`.
+impl From<std::ops::Range<chrono::NaiveDateTime>> for ParameterValue {
+    fn from(v: std::ops::Range<chrono::NaiveDateTime>) -> ParameterValue {
+        ParameterValue::Str(encode_text_range(range_bounds_to_wit(v, |d| {
+            d.format("%Y-%m-%d %H:%M:%S%.f").to_string()
+        })))
+    }
+}
+
+/// The range is passed as text, so the statement must cast the parameter,
+/// for example `$1::text::tsrange`.
+#[cfg(feature = "postgres4-types")]
+impl From<postgres_range::Range<chrono::NaiveDateTime>> for ParameterValue {
+    fn from(v: postgres_range::Range<chrono::NaiveDateTime>) -> ParameterValue {
+        if v.is_empty() {
+            return ParameterValue::Str("empty".to_owned());
+        }
+        let text = |(d, kind): (chrono::NaiveDateTime, RangeBoundKind)| {
+            (d.format("%Y-%m-%d %H:%M:%S%.f").to_string(), kind)
+        };
+        ParameterValue::Str(encode_text_range((
+            v.lower().map(pg_range_bound_to_wit).map(text),
+            v.upper().map(pg_range_bound_to_wit).map(text),
+        )))
+    }
+}
+
+/// The range is passed as text, so the statement must cast the parameter,
+/// for example `$1::text::daterange`.
+impl From<std::ops::Range<chrono::NaiveDate>> for ParameterValue {
+    fn from(v: std::ops::Range<chrono::NaiveDate>) -> ParameterValue {
+        ParameterValue::Str(encode_text_range(range_bounds_to_wit(v, |d| {
+            d.format("%Y-%m-%d").to_string()
+        })))
+    }
+}
+
+fn encode_text_range((lower, upper): TextRangeBounds<String>) -> String {
+    let bound = |bound: Option<(String, RangeBoundKind)>, brackets: [char; 2]| match bound {
+        Some((v, RangeBoundKind::Inclusive)) => (brackets[0], format!("\"{v}\"")),
+        Some((v, RangeBoundKind::Exclusive)) => (brackets[1], format!("\"{v}\"")),
+        None => (brackets[1], String::new()),
+    };
+    let (open, lower) = bound(lower, ['[', '(']);
+    let (close, upper) = bound(upper, [']', ')']);
+    format!("{open}{lower},{upper}{close}")
+}
+
 impl From<Vec<i32>> for ParameterValue {
     fn from(v: Vec<i32>) -> ParameterValue {
         ParameterValue::ArrayInt32(v.into_iter().map(Some).collect())
@@ -971,6 +2281,120 @@ impl From<Vec<String>> for ParameterValue {
     }
 }
 
+impl From<&[i32]> for ParameterValue {
+    fn from(v: &[i32]) -> ParameterValue {
+        ParameterValue::ArrayInt32(v.iter().copied().map(Some).collect())
+    }
+}
+
+impl From<&[i64]> for ParameterValue {
+    fn from(v: &[i64]) -> ParameterValue {
+        ParameterValue::ArrayInt64(v.iter().copied().map(Some).collect())
+    }
+}
+
+impl From<&[&str]> for ParameterValue {
+    fn from(v: &[&str]) -> ParameterValue {
+        ParameterValue::ArrayStr(v.iter().map(|s| Some((*s).to_owned())).collect())
+    }
+}
+
+impl From<&[String]> for ParameterValue {
+    fn from(v: &[String]) -> ParameterValue {
+        ParameterValue::ArrayStr(v.iter().cloned().map(Some).collect())
+    }
+}
+
+impl From<Vec<i16>> for ParameterValue {
+    fn from(v: Vec<i16>) -> ParameterValue {
+        ParameterValue::ArrayInt32(v.into_iter().map(|n| Some(n.into())).collect())
+    }
+}
+
+impl From<Vec<Option<i16>>> for ParameterValue {
+    fn from(v: Vec<Option<i16>>) -> ParameterValue {
+        ParameterValue::ArrayInt32(v.into_iter().map(|n| n.map(Into::into)).collect())
+    }
+}
+
+fn float_to_numeric(n: f64) -> String {
+    match n {
+        f64::INFINITY => "Infinity".to_owned(),
+        f64::NEG_INFINITY => "-Infinity".to_owned(),
+        n => n.to_string(),
+    }
+}
+
+impl From<Vec<f32>> for ParameterValue {
+    fn from(v: Vec<f32>) -> ParameterValue {
+        v.into_iter().map(Some).collect::<Vec<_>>().into()
+    }
+}
+
+impl From<Vec<Option<f32>>> for ParameterValue {
+    fn from(v: Vec<Option<f32>>) -> ParameterValue {
+        // Format the f32 itself, rather than a widened f64, to keep the
+        // shortest decimal form (0.1 rather than 0.10000000149011612).
+        let strs = v
+            .into_iter()
+            .map(|n| {
+                n.map(|n| {
+                    if n.is_infinite() {
+                        float_to_numeric(n.into())
+                    } else {
+                        n.to_string()
+                    }
+                })
+            })
+            .collect();
+        ParameterValue::ArrayDecimal(strs)
+    }
+}
+
+impl From<Vec<f64>> for ParameterValue {
+    fn from(v: Vec<f64>) -> ParameterValue {
+        v.into_iter().map(Some).collect::<Vec<_>>().into()
+    }
+}
+
+impl From<Vec<Option<f64>>> for ParameterValue {
+    fn from(v: Vec<Option<f64>>) -> ParameterValue {
+        ParameterValue::ArrayDecimal(v.into_iter().map(|n| n.map(float_to_numeric)).collect())
+    }
+}
+
+impl From<Vec<bool>> for ParameterValue {
+    fn from(v: Vec<bool>) -> ParameterValue {
+        v.into_iter().map(Some).collect::<Vec<_>>().into()
+    }
+}
+
+impl From<Vec<Vec<u8>>> for ParameterValue {
+    fn from(v: Vec<Vec<u8>>) -> ParameterValue {
+        v.into_iter().map(Some).collect::<Vec<_>>().into()
+    }
+}
+
+impl From<Vec<Option<Vec<u8>>>> for ParameterValue {
+    fn from(v: Vec<Option<Vec<u8>>>) -> ParameterValue {
+        let strs = v
+            .into_iter()
+            .map(|b| b.as_deref().map(display_bytes))
+            .collect();
+        ParameterValue::ArrayStr(strs)
+    }
+}
+
+impl From<Vec<Option<bool>>> for ParameterValue {
+    fn from(v: Vec<Option<bool>>) -> ParameterValue {
+        let strs = v
+            .into_iter()
+            .map(|b| b.map(|b| if b { "t" } else { "f" }.to_owned()))
+            .collect();
+        ParameterValue::ArrayStr(strs)
+    }
+}
+
 #[cfg(feature = "postgres4-types")]
 impl From<Vec<Option<rust_decimal::Decimal>>> for ParameterValue {
     fn from(v: Vec<Option<rust_decimal::Decimal>>) -> ParameterValue {
@@ -1005,6 +2429,33 @@ impl<T: Into<ParameterValue>> From<Option<T>> for ParameterValue {
     }
 }
 
+crate::db_value::db_value_from_parameter!(
+    Boolean,
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    Floating32,
+    Floating64,
+    Str,
+    Binary,
+    Date,
+    Time,
+    Datetime,
+    Timestamp,
+    Uuid,
+    Jsonb,
+    Decimal,
+    RangeInt32,
+    RangeInt64,
+    RangeDecimal,
+    ArrayInt32,
+    ArrayInt64,
+    ArrayDecimal,
+    ArrayStr,
+    Interval,
+);
+
 fn format_decode_err(types: &str, value: &DbValue) -> String {
     format!("Expected {} from the DB but got {:?}", types, value)
 }
@@ -1015,6 +2466,85 @@ mod tests {
 
     use super::*;
 
+    crate::db_value::round_trip_helper!();
+
+    #[test]
+    fn round_trips() {
+        round_trip(true);
+        round_trip(i16::MIN);
+        round_trip(i32::MAX);
+        round_trip(i64::MIN);
+        round_trip(1.5f32);
+        round_trip(-2.25f64);
+        round_trip("hello".to_owned());
+        round_trip(vec![0u8, 255]);
+        round_trip([0xdeu8, 0xad, 0xbe, 0xef]);
+        assert!(matches!(ParameterValue::from("hi"), ParameterValue::Str(s) if s == "hi"));
+        assert!(
+            matches!(ParameterValue::from(&b"hi"[..]), ParameterValue::Binary(b) if b == b"hi")
+        );
+        round_trip(Some(7i32));
+        round_trip(None::<String>);
+        round_trip(chrono::NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+        round_trip(chrono::NaiveTime::from_hms_nano_opt(23, 59, 59, 123_456_789).unwrap());
+        round_trip(
+            chrono::NaiveDate::from_ymd_opt(1999, 12, 31)
+                .unwrap()
+                .and_hms_nano_opt(1, 2, 3, 4)
+                .unwrap(),
+        );
+        round_trip(chrono::Duration::seconds(-86_400));
+        round_trip(Interval {
+            micros: 1,
+            days: -2,
+            months: 3,
+        });
+        round_trip(vec![Some(1i32), None]);
+        round_trip(vec![Some(i64::MAX), None]);
+        round_trip(vec![Some("a".to_owned()), None]);
+        round_trip(vec![Some(-3i16), None]);
+        round_trip(vec![Some(0.5f32), None]);
+        round_trip(vec![Some(f64::INFINITY), None]);
+        round_trip(vec![Some(true), None, Some(false)]);
+        round_trip(vec![Some(vec![1u8, 2]), None, Some(vec![])]);
+
+        #[cfg(feature = "json")]
+        {
+            let json = serde_json::json!({"a": [1, null, "b"]});
+            let db_value = DbValue::from(ParameterValue::try_from(json.clone()).unwrap());
+            assert_eq!(json, serde_json::Value::decode(&db_value).unwrap());
+        }
+
+        #[cfg(feature = "postgres4-types")]
+        {
+            use std::str::FromStr;
+
+            round_trip(uuid::Uuid::from_u128(0x1234_5678_9abc_def0));
+            round_trip(rust_decimal::Decimal::from_str("-1234.5678").unwrap());
+            round_trip(vec![
+                Some(rust_decimal::Decimal::from_str("0.1").unwrap()),
+                None,
+            ]);
+            round_trip(postgres_range::Range::new(
+                Some(postgres_range::RangeBound::new(
+                    1i32,
+                    postgres_range::BoundType::Inclusive,
+                )),
+                Some(postgres_range::RangeBound::new(
+                    10,
+                    postgres_range::BoundType::Exclusive,
+                )),
+            ));
+            round_trip(postgres_range::Range::new(
+                None,
+                Some(postgres_range::RangeBound::new(
+                    5i64,
+                    postgres_range::BoundType::Inclusive,
+                )),
+            ));
+        }
+    }
+
     #[test]
     fn boolean() {
         assert!(bool::decode(&DbValue::Boolean(true)).unwrap());
@@ -1076,6 +2606,11 @@ mod tests {
     fn binary() {
         assert!(Vec::<u8>::decode(&DbValue::Binary(vec![0, 0])).is_ok());
         assert!(Vec::<u8>::decode(&DbValue::Boolean(false)).is_err());
+        assert_eq!(
+            [1u8, 2],
+            <[u8; 2]>::decode(&DbValue::Binary(vec![1, 2])).unwrap()
+        );
+        assert!(<[u8; 16]>::decode(&DbValue::Binary(vec![1, 2])).is_err());
         assert!(
             Option::<Vec<u8>>::decode(&DbValue::DbNull)
                 .unwrap()
@@ -1155,6 +2690,79 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg(feature = "pg-time")]
+    fn time_crate_date() {
+        let date = time::Date::from_calendar_date(1, time::Month::February, 4).unwrap();
+        assert_eq!(date, time::Date::decode(&DbValue::Date((1, 2, 4))).unwrap());
+        assert_ne!(date, time::Date::decode(&DbValue::Date((1, 2, 5))).unwrap());
+        assert!(time::Date::decode(&DbValue::Date((1, 13, 4))).is_err());
+        assert!(time::Date::decode(&DbValue::Int32(1)).is_err());
+        assert!(
+            Option::<time::Date>::decode(&DbValue::DbNull)
+                .unwrap()
+                .is_none()
+        );
+        assert!(matches!(
+            ParameterValue::from(date),
+            ParameterValue::Date((1, 2, 4))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "pg-time")]
+    fn time_crate_time() {
+        let time = time::Time::from_hms_nano(1, 2, 3, 4).unwrap();
+        assert_eq!(
+            time,
+            time::Time::decode(&DbValue::Time((1, 2, 3, 4))).unwrap()
+        );
+        assert_ne!(
+            time,
+            time::Time::decode(&DbValue::Time((1, 2, 4, 5))).unwrap()
+        );
+        assert!(time::Time::decode(&DbValue::Time((24, 0, 0, 0))).is_err());
+        assert!(
+            Option::<time::Time>::decode(&DbValue::DbNull)
+                .unwrap()
+                .is_none()
+        );
+        assert!(matches!(
+            ParameterValue::from(time),
+            ParameterValue::Time((1, 2, 3, 4))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "pg-time")]
+    fn time_crate_datetime() {
+        let date = time::Date::from_calendar_date(1, time::Month::February, 3).unwrap();
+        let time = time::Time::from_hms_nano(4, 5, 6, 7).unwrap();
+        let datetime = time::PrimitiveDateTime::new(date, time);
+        let value = DbValue::Datetime((1, 2, 3, 4, 5, 6, 7));
+        assert_eq!(datetime, time::PrimitiveDateTime::decode(&value).unwrap());
+        assert_eq!(
+            datetime.assume_utc(),
+            time::OffsetDateTime::decode(&value).unwrap()
+        );
+        assert!(
+            Option::<time::PrimitiveDateTime>::decode(&DbValue::DbNull)
+                .unwrap()
+                .is_none()
+        );
+        assert!(matches!(
+            ParameterValue::from(datetime),
+            ParameterValue::Datetime((1, 2, 3, 4, 5, 6, 7))
+        ));
+
+        let offset = time::UtcOffset::from_hms(2, 0, 0).unwrap();
+        let later = time::PrimitiveDateTime::new(date, time::Time::from_hms(6, 5, 6).unwrap());
+        assert!(matches!(
+            ParameterValue::from(later.assume_offset(offset)),
+            ParameterValue::Datetime((1, 2, 3, 4, 5, 6, 0))
+        ));
+    }
+
     #[test]
     #[cfg(feature = "postgres4-types")]
     fn uuid() {
@@ -1191,6 +2799,43 @@ mod tests {
         assert_eq!(json_struct, from_jsonb(&dbval).unwrap());
     }
 
+    #[cfg(feature = "postgres4-types")]
+    #[test]
+    fn money() {
+        use std::str::FromStr;
+        let dec = |s| rust_decimal::Decimal::from_str(s).unwrap();
+        let money = |s: &str| decode_money(&DbValue::Str(s.to_owned())).unwrap();
+
+        assert_eq!(dec("1234.56"), money("$1,234.56"));
+        assert_eq!(dec("-1234.56"), money("-$1,234.56"));
+        assert_eq!(dec("-1.00"), money("($1.00)"));
+        assert_eq!(dec("1234.56"), money("1.234,56 €"));
+        assert_eq!(dec("1234567"), money("¥1,234,567"));
+        assert_eq!(dec("1234"), money("1,234"));
+        assert_eq!(dec("0.5"), money("$0.5"));
+        assert_eq!(
+            dec("12.345"),
+            decode_money(&DbValue::Decimal("12.345".to_owned())).unwrap()
+        );
+        assert!(decode_money(&DbValue::Str("$".to_owned())).is_err());
+        assert!(decode_money(&DbValue::Int64(1)).is_err());
+    }
+
+    #[test]
+    fn numeric_as_f64() {
+        assert_eq!(
+            decode_numeric_as_f64(&DbValue::Decimal("1234.5".to_owned())).unwrap(),
+            1234.5
+        );
+        assert!(
+            decode_numeric_as_f64(&DbValue::Decimal("NaN".to_owned()))
+                .unwrap()
+                .is_nan()
+        );
+        assert!(decode_numeric_as_f64(&DbValue::Decimal("abc".to_owned())).is_err());
+        assert!(decode_numeric_as_f64(&DbValue::Floating64(1.0)).is_err());
+    }
+
     #[test]
     #[cfg(feature = "postgres4-types")]
     fn ranges() {
@@ -1270,4 +2915,445 @@ mod tests {
         let str_arr = Vec::<Option<String>>::decode(&DbValue::ArrayStr(vstr.clone())).unwrap();
         assert_eq!(vstr, str_arr);
     }
+
+    #[test]
+    fn slice_arrays() {
+        let ids: &[i32] = &[1, 2];
+        assert!(matches!(
+            ParameterValue::from(ids),
+            ParameterValue::ArrayInt32(v) if v == [Some(1), Some(2)]
+        ));
+        let ids: &[i64] = &[3];
+        assert!(matches!(
+            ParameterValue::from(ids),
+            ParameterValue::ArrayInt64(v) if v == [Some(3)]
+        ));
+        let names: &[&str] = &["alice", "bob"];
+        assert!(matches!(
+            ParameterValue::from(names),
+            ParameterValue::ArrayStr(v) if v == [Some("alice".to_owned()), Some("bob".to_owned())]
+        ));
+        let names = vec!["carol".to_owned()];
+        assert!(matches!(
+            ParameterValue::from(names.as_slice()),
+            ParameterValue::ArrayStr(v) if v == [Some("carol".to_owned())]
+        ));
+    }
+
+    #[test]
+    fn narrow_and_text_arrays() {
+        assert_eq!(
+            vec![Some(1i16), None],
+            Vec::<Option<i16>>::decode(&DbValue::ArrayInt32(vec![Some(1), None])).unwrap()
+        );
+        assert!(Vec::<Option<i16>>::decode(&DbValue::ArrayInt32(vec![Some(40_000)])).is_err());
+
+        let floats = DbValue::ArrayDecimal(vec![
+            Some("0.1".to_owned()),
+            None,
+            Some("-Infinity".to_owned()),
+        ]);
+        assert_eq!(
+            vec![Some(0.1f32), None, Some(f32::NEG_INFINITY)],
+            Vec::<Option<f32>>::decode(&floats).unwrap()
+        );
+        assert_eq!(
+            vec![Some(0.1f64), None, Some(f64::NEG_INFINITY)],
+            Vec::<Option<f64>>::decode(&floats).unwrap()
+        );
+        assert!(Vec::<Option<f64>>::decode(&DbValue::ArrayStr(vec![])).is_err());
+
+        let bools = DbValue::ArrayStr(vec![Some("t".to_owned()), None, Some("false".to_owned())]);
+        assert_eq!(
+            vec![Some(true), None, Some(false)],
+            Vec::<Option<bool>>::decode(&bools).unwrap()
+        );
+        assert!(
+            Vec::<Option<bool>>::decode(&DbValue::ArrayStr(vec![Some("yes".to_owned())])).is_err()
+        );
+
+        assert!(matches!(
+            ParameterValue::from(vec![Some(0.1f32), Some(f32::INFINITY)]),
+            ParameterValue::ArrayDecimal(a) if a == [Some("0.1".to_owned()), Some("Infinity".to_owned())]
+        ));
+        assert!(matches!(
+            ParameterValue::from(vec![true, false]),
+            ParameterValue::ArrayStr(a) if a == [Some("t".to_owned()), Some("f".to_owned())]
+        ));
+        assert!(matches!(
+            ParameterValue::from(vec![-2i16]),
+            ParameterValue::ArrayInt32(a) if a == [Some(-2)]
+        ));
+    }
+
+    #[test]
+    fn bytea_arrays() {
+        assert_eq!(vec![0xde, 0xad], decode_bytea("\\xDEad").unwrap());
+        assert_eq!(Vec::<u8>::new(), decode_bytea("\\x").unwrap());
+        assert_eq!(b"a\\\x01b".to_vec(), decode_bytea("a\\\\\\001b").unwrap());
+        assert!(decode_bytea("\\xabc").is_err());
+        assert!(decode_bytea("\\xzz").is_err());
+        assert!(decode_bytea("bad\\9").is_err());
+
+        let arr = DbValue::ArrayStr(vec![Some("\\x0102".to_owned()), None]);
+        assert_eq!(
+            vec![Some(vec![1, 2]), None],
+            Vec::<Option<Vec<u8>>>::decode(&arr).unwrap()
+        );
+        assert!(matches!(
+            ParameterValue::from(vec![vec![0xffu8], vec![]]),
+            ParameterValue::ArrayStr(a) if a == [Some("\\xff".to_owned()), Some("\\x".to_owned())]
+        ));
+    }
+
+    #[test]
+    fn bits() {
+        let bits = vec![true, false, true, true];
+        assert_eq!(bits, decode_bits(&DbValue::Str("1011".to_owned())).unwrap());
+        assert!(
+            decode_bits(&DbValue::Str(String::new()))
+                .unwrap()
+                .is_empty()
+        );
+        assert!(decode_bits(&DbValue::Str("10x".to_owned())).is_err());
+        assert!(decode_bits(&DbValue::Int32(1)).is_err());
+        assert!(matches!(encode_bits(&bits), ParameterValue::Str(s) if s == "1011"));
+    }
+
+    #[test]
+    fn records() {
+        let record = |s: &str| decode_record(&DbValue::Str(s.to_owned()));
+        let text = |s: &str| DbValue::Str(s.to_owned());
+        assert_eq!(
+            vec![text("1"), text("Baldrick"), DbValue::DbNull],
+            record("(1,Baldrick,)").unwrap()
+        );
+        assert_eq!(
+            vec![
+                text("a, \"b\""),
+                text(""),
+                text("back\\slash"),
+                text("(2,\"x y\")")
+            ],
+            record(r#"("a, ""b""","","back\\slash","(2,\"x y\")")"#).unwrap()
+        );
+        assert_eq!(vec![DbValue::DbNull], record("()").unwrap());
+        assert_eq!(
+            vec![text("2"), text("x y")],
+            decode_record(&record(r#"("(2,\"x y\")")"#).unwrap()[0]).unwrap()
+        );
+        assert!(record("1,2").is_err());
+        assert!(record("(\"open)").is_err());
+        assert!(decode_record(&DbValue::Int32(1)).is_err());
+    }
+
+    #[test]
+    fn temporal_ranges() {
+        type Bounds<T> = (Option<(T, RangeBoundKind)>, Option<(T, RangeBoundKind)>);
+        let text = |s: &str| DbValue::Str(s.to_owned());
+        let date = |d| chrono::NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+        let datetime = |d, ms| date(d).and_hms_milli_opt(10, 30, 0, ms).unwrap();
+
+        let (lower, upper) = Bounds::<chrono::NaiveDateTime>::decode(&text(
+            r#"["2024-01-01 10:30:00.5","2024-01-02 10:30:00")"#,
+        ))
+        .unwrap();
+        assert_eq!(Some((datetime(1, 500), RangeBoundKind::Inclusive)), lower);
+        assert_eq!(Some((datetime(2, 0), RangeBoundKind::Exclusive)), upper);
+        assert!(matches!(
+            ParameterValue::from(datetime(1, 500)..datetime(2, 0)),
+            ParameterValue::Str(s) if s == r#"["2024-01-01 10:30:00.500","2024-01-02 10:30:00")"#
+        ));
+
+        let (lower, upper) =
+            Bounds::<chrono::NaiveDate>::decode(&text("[2024-01-01,2024-01-05)")).unwrap();
+        assert_eq!(Some((date(1), RangeBoundKind::Inclusive)), lower);
+        assert_eq!(Some((date(5), RangeBoundKind::Exclusive)), upper);
+        assert!(matches!(
+            ParameterValue::from(date(1)..date(5)),
+            ParameterValue::Str(s) if s == r#"["2024-01-01","2024-01-05")"#
+        ));
+
+        assert_eq!(
+            (None, Some((date(5), RangeBoundKind::Exclusive))),
+            Bounds::<chrono::NaiveDate>::decode(&text("(,2024-01-05)")).unwrap()
+        );
+        assert_eq!(
+            (Some((date(1), RangeBoundKind::Inclusive)), None),
+            Bounds::<chrono::NaiveDate>::decode(&text("[2024-01-01,infinity)")).unwrap()
+        );
+        assert!(Bounds::<chrono::NaiveDate>::decode(&text("empty")).is_err());
+        assert!(Bounds::<chrono::NaiveDate>::decode(&text("[2024-01-01)")).is_err());
+        assert!(Bounds::<chrono::NaiveDate>::decode(&text("[2024-13-01,)")).is_err());
+        assert!(Bounds::<chrono::NaiveDate>::decode(&DbValue::Int32(1)).is_err());
+    }
+
+    #[cfg(feature = "postgres4-types")]
+    #[test]
+    fn timestamp_ranges() {
+        use postgres_range::{BoundType, Range};
+
+        let text = |s: &str| DbValue::Str(s.to_owned());
+        let datetime = |d| {
+            chrono::NaiveDate::from_ymd_opt(2024, 1, d)
+                .unwrap()
+                .and_hms_opt(9, 0, 0)
+                .unwrap()
+        };
+
+        let range = Range::<chrono::NaiveDateTime>::decode(&text(
+            r#"["2024-01-01 09:00:00","2024-01-02 09:00:00")"#,
+        ))
+        .unwrap();
+        let lower = range.lower().unwrap();
+        assert_eq!(
+            (datetime(1), BoundType::Inclusive),
+            (lower.value, lower.type_)
+        );
+        let upper = range.upper().unwrap();
+        assert_eq!(
+            (datetime(2), BoundType::Exclusive),
+            (upper.value, upper.type_)
+        );
+        assert!(matches!(
+            ParameterValue::from(range),
+            ParameterValue::Str(s) if s == r#"["2024-01-01 09:00:00","2024-01-02 09:00:00")"#
+        ));
+
+        let range =
+            Range::<chrono::NaiveDateTime>::decode(&text("[2024-01-01 09:00:00,)")).unwrap();
+        assert!(range.upper().is_none());
+
+        let empty = Range::<chrono::NaiveDateTime>::decode(&text("empty")).unwrap();
+        assert!(empty.is_empty());
+        assert!(matches!(ParameterValue::from(empty), ParameterValue::Str(s) if s == "empty"));
+        assert!(Range::<chrono::NaiveDateTime>::decode(&DbValue::Int32(1)).is_err());
+    }
+
+    #[test]
+    fn row_streams() {
+        use futures::{StreamExt, executor::block_on};
+
+        let columns = Arc::new(vec![]);
+        let row = |id| Row {
+            columns: columns.clone(),
+            result: vec![DbValue::Int32(id)],
+        };
+        let ids = |result: Result<(), PgError>| {
+            let rows = futures::stream::iter([row(1), row(2), row(3)]);
+            let stream = rows_then_result(rows, std::future::ready(result));
+            block_on(stream.collect::<Vec<_>>())
+                .into_iter()
+                .map(|row| row.map(|row| i32::decode(&row[0]).unwrap()))
+                .collect::<Vec<_>>()
+        };
+
+        let all = ids(Ok(()));
+        assert_eq!(3, all.len());
+        assert!(
+            all.iter()
+                .zip(1..)
+                .all(|(id, n)| id.as_ref().is_ok_and(|id| *id == n))
+        );
+
+        let failed = ids(Err(PgError::Other("connection lost".to_owned())));
+        assert_eq!(4, failed.len());
+        assert!(matches!(
+            failed.last(),
+            Some(Err(Error::PgError(PgError::Other(msg)))) if msg == "connection lost"
+        ));
+    }
+
+    #[test]
+    fn typed_rows() {
+        #[derive(Debug, PartialEq, FromRow)]
+        struct Cat {
+            name: String,
+        }
+
+        #[derive(Debug, PartialEq, FromRow)]
+        struct User {
+            id: i32,
+            name: String,
+            age: Option<i16>,
+        }
+
+        let column = |name: &str, data_type| Column {
+            name: name.to_owned(),
+            data_type,
+        };
+        let users = Arc::new(vec![
+            column("id", DbDataType::Int32),
+            column("name", DbDataType::Str),
+            column("age", DbDataType::Int16),
+        ]);
+        let row = |result| Row {
+            columns: users.clone(),
+            result,
+        };
+
+        let jane = row(vec![
+            DbValue::Int32(1),
+            DbValue::Str("Jane".to_owned()),
+            DbValue::DbNull,
+        ]);
+        assert_eq!(
+            User {
+                id: 1,
+                name: "Jane".to_owned(),
+                age: None,
+            },
+            User::from_row(&jane).unwrap()
+        );
+
+        let cats = Arc::new(vec![column("name", DbDataType::Str)]);
+        let slats = Row {
+            columns: cats,
+            result: vec![DbValue::Str("Slats".to_owned())],
+        };
+        assert_eq!(
+            Cat {
+                name: "Slats".to_owned()
+            },
+            Cat::from_row(&slats).unwrap()
+        );
+        assert!(matches!(
+            User::from_row(&slats),
+            Err(Error::Decode(msg)) if msg.contains("`id`")
+        ));
+
+        let wrong = row(vec![
+            DbValue::Int32(2),
+            DbValue::Int32(3),
+            DbValue::Int16(4),
+        ]);
+        assert!(matches!(
+            User::from_row(&wrong),
+            Err(Error::Decode(msg)) if msg.starts_with("column `name`")
+        ));
+    }
+
+    #[test]
+    fn row_count_errors() {
+        assert_eq!(
+            "expected a single row, but the query returned none",
+            Error::UnexpectedRowCount(0).to_string()
+        );
+        assert_eq!(
+            "expected a single row, but the query returned more than one",
+            Error::UnexpectedRowCount(2).to_string()
+        );
+    }
+
+    #[test]
+    fn display_values() {
+        assert_eq!("NULL", display_value(&DbValue::DbNull));
+        assert_eq!("42", DbValue::Int32(42).to_string());
+        assert_eq!(
+            DbValue::ArrayInt32(vec![Some(1), None]),
+            DbValue::ArrayInt32(vec![Some(1), None])
+        );
+        assert_ne!(DbValue::Int32(1), DbValue::Int64(1));
+        assert_eq!(
+            "\\xdead01",
+            display_value(&DbValue::Binary(vec![0xde, 0xad, 0x01]))
+        );
+        assert_eq!("2024-02-09", display_value(&DbValue::Date((2024, 2, 9))));
+        assert_eq!(
+            "2024-02-09 08:05:03.25",
+            display_value(&DbValue::Datetime((2024, 2, 9, 8, 5, 3, 250_000_000)))
+        );
+        assert_eq!(
+            "[1,10)",
+            display_value(&DbValue::RangeInt32((
+                Some((1, RangeBoundKind::Inclusive)),
+                Some((10, RangeBoundKind::Exclusive))
+            )))
+        );
+        assert_eq!(
+            "(,5]",
+            display_value(&DbValue::RangeInt64((
+                None,
+                Some((5, RangeBoundKind::Inclusive))
+            )))
+        );
+        assert_eq!(
+            "{1,NULL,3}",
+            display_value(&DbValue::ArrayInt32(vec![Some(1), None, Some(3)]))
+        );
+        assert_eq!(
+            r#"{a,"b c","",NULL,"null","q\"d"}"#,
+            display_value(&DbValue::ArrayStr(vec![
+                Some("a".to_owned()),
+                Some("b c".to_owned()),
+                Some("".to_owned()),
+                None,
+                Some("null".to_owned()),
+                Some("q\"d".to_owned()),
+            ]))
+        );
+        assert_eq!(
+            "1 year 2 mons 3 days -00:00:01.5",
+            display_value(&DbValue::Interval(Interval {
+                micros: -1_500_000,
+                days: 3,
+                months: 14
+            }))
+        );
+        assert_eq!(
+            "00:00:00",
+            display_value(&DbValue::Interval(Interval {
+                micros: 0,
+                days: 0,
+                months: 0
+            }))
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_values() {
+        use serde_json::json;
+
+        assert_eq!(json!(null), to_json_value(&DbValue::DbNull));
+        assert_eq!(json!(42), to_json_value(&DbValue::Int32(42)));
+        assert_eq!(json!(null), to_json_value(&DbValue::Floating64(f64::NAN)));
+        assert_eq!(
+            json!("0xdead"),
+            to_json_value(&DbValue::Binary(vec![0xde, 0xad]))
+        );
+        assert_eq!(
+            json!("1.10"),
+            to_json_value(&DbValue::Decimal("1.10".to_owned()))
+        );
+        let numbers = JsonOptions {
+            numeric_as_number: true,
+        };
+        assert_eq!(
+            json!(1.1),
+            to_json_value_with(&DbValue::Decimal("1.10".to_owned()), &numbers)
+        );
+        assert_eq!(
+            json!([2.5, null]),
+            to_json_value_with(
+                &DbValue::ArrayDecimal(vec![Some("2.5".to_owned()), None]),
+                &numbers
+            )
+        );
+        assert_eq!(
+            json!({"a": [1]}),
+            to_json_value(&DbValue::Jsonb(br#"{"a":[1]}"#.to_vec()))
+        );
+        assert_eq!(
+            json!([1, null]),
+            to_json_value(&DbValue::ArrayInt64(vec![Some(1), None]))
+        );
+        assert_eq!(
+            json!({"lower": "1.5", "upper": null, "lower_inclusive": false, "upper_inclusive": false}),
+            to_json_value(&DbValue::RangeDecimal((
+                Some(("1.5".to_owned(), RangeBoundKind::Exclusive)),
+                None
+            )))
+        );
+    }
 }
@@ -39,7 +39,16 @@ pub mod wit {
     pub use spin::redis::redis;
 }
 
+use crate::retry::RetryPolicy;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+thread_local! {
+    /// Connections opened by [`Connection::open_shared`], by address.
+    static SHARED: RefCell<HashMap<String, Rc<Connection>>> = RefCell::default();
+}
 
 /// An open connection to a Redis server.
 ///
@@ -111,6 +120,66 @@ impl Connection {
             .map(Connection)
     }
 
+    /// Open a connection to the Redis instance at `address`, retrying if the
+    /// connection cannot be established.
+    ///
+    /// An invalid address is reported immediately; other connection errors
+    /// are retried according to `policy`. If all attempts fail, the error
+    /// from the last attempt is returned.
+    pub async fn open_with_retry(
+        address: impl AsRef<str>,
+        policy: RetryPolicy,
+    ) -> Result<Self, Error> {
+        let address = address.as_ref();
+        crate::retry::retry(
+            &policy,
+            || Self::open(address),
+            |e| matches!(e, Error::TooManyConnections | Error::Other(_)),
+        )
+        .await
+    }
+
+    /// Open a connection to the Redis instance at `address`, or return the
+    /// one already opened to it by an earlier call.
+    ///
+    /// Use this when one invocation talks to the same server from several
+    /// places, such as publishing in a loop, so that each call does not open
+    /// its own connection. Shared connections stay open until the component
+    /// instance exits, which Spin does after each request or message, or
+    /// until [`clear_shared`](Self::clear_shared) is called. A connection
+    /// that has failed is not replaced automatically, so clear it before
+    /// opening again.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use spin_sdk::redis::Connection;
+    ///
+    /// # async fn run() -> anyhow::Result<()> {
+    /// for id in 0..100 {
+    ///     let conn = Connection::open_shared("redis://127.0.0.1:6379").await?;
+    ///     conn.publish("orders", id.to_string()).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn open_shared(address: impl AsRef<str>) -> Result<Rc<Self>, Error> {
+        let address = address.as_ref();
+        if let Some(conn) = SHARED.with_borrow(|shared| shared.get(address).cloned()) {
+            return Ok(conn);
+        }
+        let conn = Rc::new(Self::open(address).await?);
+        SHARED.with_borrow_mut(|shared| shared.insert(address.to_owned(), conn.clone()));
+        Ok(conn)
+    }
+
+    /// Forget the connections opened by [`open_shared`](Self::open_shared).
+    ///
+    /// Each connection is closed once the last handle to it is dropped.
+    pub fn clear_shared() {
+        SHARED.with_borrow_mut(HashMap::clear);
+    }
+
     /// Publish a Redis message to the specified channel.
     pub async fn publish(
         &self,
@@ -0,0 +1,124 @@
+//! Retrying operations that may fail transiently.
+//!
+//! Opening a connection to a database or other service can fail briefly,
+//! for example while the service is being deployed alongside the component.
+//! The `open_with_retry` constructors on connection types accept a
+//! [`RetryPolicy`] describing how many times, and how often, to try again.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use spin_sdk::pg::Connection;
+//! use spin_sdk::retry::RetryPolicy;
+//! use std::time::Duration;
+//!
+//! # async fn run() -> anyhow::Result<()> {
+//! let policy = RetryPolicy::new(5).with_initial_backoff(Duration::from_millis(200));
+//! let db = Connection::open_with_retry("host=localhost dbname=mydb", policy).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::time::Duration;
+
+/// Describes how a failed operation should be retried.
+///
+/// After each failed attempt the policy waits before trying again. The
+/// first wait is the initial backoff, and each subsequent wait is double
+/// the previous one, up to the maximum backoff.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    /// Five attempts, starting with a 100ms backoff and capped at 2s.
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(2),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Creates a policy that makes at most `max_attempts` attempts in total.
+    ///
+    /// An operation is always attempted at least once, even if `max_attempts` is zero.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Default::default()
+        }
+    }
+
+    /// Sets how long to wait after the first failed attempt.
+    pub fn with_initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Sets the longest time to wait between attempts.
+    pub fn with_max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// The maximum number of attempts.
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// How long to wait after the given (zero-based) failed attempt.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Runs `op` until it succeeds, it fails with an error that `is_transient`
+/// rejects, or the policy's attempts are exhausted. The last error is returned.
+#[cfg(any(feature = "mysql", feature = "pg", feature = "redis"))]
+pub(crate) async fn retry<T, E, Fut>(
+    policy: &RetryPolicy,
+    mut op: impl FnMut() -> Fut,
+    is_transient: impl Fn(&E) -> bool,
+) -> Result<T, E>
+where
+    Fut: std::future::Future<Output = Result<T, E>>,
+{
+    let mut attempt = 0;
+    loop {
+        match op().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt + 1 < policy.max_attempts && is_transient(&e) => {
+                crate::time::sleep(policy.backoff(attempt)).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        let policy = RetryPolicy::new(10)
+            .with_initial_backoff(Duration::from_millis(100))
+            .with_max_backoff(Duration::from_millis(500));
+
+        assert_eq!(Duration::from_millis(100), policy.backoff(0));
+        assert_eq!(Duration::from_millis(200), policy.backoff(1));
+        assert_eq!(Duration::from_millis(400), policy.backoff(2));
+        assert_eq!(Duration::from_millis(500), policy.backoff(3));
+        assert_eq!(Duration::from_millis(500), policy.backoff(40));
+    }
+}
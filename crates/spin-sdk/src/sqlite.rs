@@ -21,6 +21,10 @@ pub mod wit {
 #[doc(inline)]
 pub use wit::sqlite::{Error, Value};
 
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+pub use crate::db_json::JsonOptions;
+
 /// An open connection to a SQLite database.
 ///
 /// [Connection::execute()] returns a tuple of `(columns, rows_stream, finish_future)`
@@ -131,6 +135,75 @@ impl Connection {
         })
     }
 
+    /// Execute a query that must return exactly one row.
+    ///
+    /// Returns [`QueryError::UnexpectedRowCount`] if the query returns no
+    /// rows or more than one.
+    pub async fn query_one(
+        &self,
+        statement: impl AsRef<str>,
+        parameters: impl IntoIterator<Item = Value>,
+    ) -> Result<RowResult, QueryError> {
+        self.query_opt(statement, parameters)
+            .await?
+            .ok_or(QueryError::UnexpectedRowCount(0))
+    }
+
+    /// Execute a query that returns at most one row.
+    ///
+    /// Returns `None` if the query returns no rows, and
+    /// [`QueryError::UnexpectedRowCount`] if it returns more than one. Only
+    /// the first two rows are read, so a query that returns many rows fails
+    /// without fetching them all.
+    pub async fn query_opt(
+        &self,
+        statement: impl AsRef<str>,
+        parameters: impl IntoIterator<Item = Value>,
+    ) -> Result<Option<RowResult>, QueryError> {
+        let mut rows = self.execute(statement, parameters).await?;
+        let Some(row) = rows.next().await else {
+            rows.result().await?;
+            return Ok(None);
+        };
+        if rows.next().await.is_some() {
+            // Dropping the result abandons the remaining rows.
+            return Err(QueryError::UnexpectedRowCount(2));
+        }
+        rows.result().await?;
+        Ok(Some(row))
+    }
+
+    /// Execute a query for a single value, such as the result of
+    /// `SELECT COUNT(*)`.
+    ///
+    /// The query must return exactly one row, and the value in its first
+    /// column is converted to `T` as described in [`RowResult::get`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn run() -> anyhow::Result<()> {
+    /// use spin_sdk::sqlite::Connection;
+    ///
+    /// let db = Connection::open_default().await?;
+    /// let count = db.query_scalar::<i64>("SELECT COUNT(*) FROM users", []).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn query_scalar<T>(
+        &self,
+        statement: impl AsRef<str>,
+        parameters: impl IntoIterator<Item = Value>,
+    ) -> Result<T, QueryError>
+    where
+        T: for<'a> TryFrom<&'a Value>,
+    {
+        let row = self.query_one(statement, parameters).await?;
+        let value = row.values.into_iter().next().ok_or(QueryError::NoColumns)?;
+        let converted = T::try_from(&value).ok();
+        converted.ok_or(QueryError::Conversion(value))
+    }
+
     /// The SQLite rowid of the most recent successful INSERT on the connection, or 0 if
     /// there has not yet been an INSERT on the connection.
     pub async fn last_insert_rowid(&self) -> i64 {
@@ -181,6 +254,39 @@ impl QueryResult {
         Ok(rows)
     }
 
+    /// Collect all rows in the result set as a JSON array of objects keyed
+    /// by column name.
+    ///
+    /// Values are converted as described in [`to_json_value`]. Like
+    /// [`QueryResult::collect`], this is only suitable for result sets small
+    /// enough to fit in memory.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn run() -> anyhow::Result<()> {
+    /// use spin_sdk::sqlite::{Connection, JsonOptions};
+    ///
+    /// let db = Connection::open_default().await?;
+    /// let users = db
+    ///     .execute("SELECT * FROM users", [])
+    ///     .await?
+    ///     .collect_json(&JsonOptions::default())
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub async fn collect_json(self, options: &JsonOptions) -> Result<serde_json::Value, Error> {
+        let columns = self.columns.clone();
+        let rows = self.collect().await?;
+        Ok(rows
+            .iter()
+            .map(|row| row.to_json(&columns, options))
+            .collect())
+    }
+
     /// Extracts the underlying Wasm Component Model results of the query.
     #[allow(clippy::type_complexity, reason = "that's what the inner bits are")]
     pub fn into_inner(
@@ -265,6 +371,77 @@ impl RowResult {
     }
 }
 
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+impl RowResult {
+    /// Convert the row to a JSON object keyed by column name, taking the
+    /// names from [`QueryResult::columns`].
+    ///
+    /// Values are converted with [`to_json_value_with`]. If several columns
+    /// have the same name, the last one wins.
+    pub fn to_json(&self, columns: &[String], options: &JsonOptions) -> serde_json::Value {
+        columns
+            .iter()
+            .zip(&self.values)
+            .map(|(column, value)| (column.clone(), to_json_value_with(value, options)))
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    }
+}
+
+/// Convert a [`Value`] to a [`serde_json::Value`].
+///
+/// This is intended for generic tooling, such as APIs that return arbitrary
+/// result sets. Integers and reals become JSON numbers, text stays text, and
+/// `NULL` becomes `null`. Blobs become hex strings prefixed with `0x`, and
+/// non-finite reals become `null`.
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+pub fn to_json_value(value: &Value) -> serde_json::Value {
+    to_json_value_with(value, &JsonOptions::default())
+}
+
+/// Convert a [`Value`] to a [`serde_json::Value`], as [`to_json_value`]
+/// does, using the given options.
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+pub fn to_json_value_with(value: &Value, options: &JsonOptions) -> serde_json::Value {
+    use crate::db_json::Scalar;
+
+    options.convert(match value {
+        Value::Integer(n) => Scalar::Int(*n),
+        Value::Real(n) => Scalar::Float(*n),
+        Value::Text(s) => Scalar::Text(s),
+        Value::Blob(b) => Scalar::Bytes(b),
+        Value::Null => Scalar::Null,
+    })
+}
+
+/// An error from [`Connection::query_one`], [`Connection::query_opt`] or
+/// [`Connection::query_scalar`].
+#[derive(Debug, thiserror::Error)]
+pub enum QueryError {
+    /// The query failed.
+    #[error(transparent)]
+    Sqlite(#[from] Error),
+    /// A query expected to return a single row returned none, or more than
+    /// one.
+    ///
+    /// The count is 0 for no rows. As rows after the second are not read, it
+    /// is 2 for any larger number of rows.
+    #[error(
+        "expected a single row, but the query returned {}",
+        if *.0 == 0 { "none" } else { "more than one" }
+    )]
+    UnexpectedRowCount(usize),
+    /// A query expected to return a value returned no columns.
+    #[error("query returned no columns")]
+    NoColumns,
+    /// The value could not be converted to the requested type.
+    #[error("cannot convert {0:?} to the requested type")]
+    Conversion(Value),
+}
+
 impl<'a> TryFrom<&'a Value> for bool {
     type Error = ();
 
@@ -316,6 +493,22 @@ impl<'a> TryFrom<&'a Value> for &'a str {
     }
 }
 
+impl<'a> TryFrom<&'a Value> for String {
+    type Error = ();
+
+    fn try_from(value: &'a Value) -> Result<Self, Self::Error> {
+        <&str>::try_from(value).map(str::to_owned)
+    }
+}
+
+impl<'a> TryFrom<&'a Value> for Vec<u8> {
+    type Error = ();
+
+    fn try_from(value: &'a Value) -> Result<Self, Self::Error> {
+        <&[u8]>::try_from(value).map(<[u8]>::to_vec)
+    }
+}
+
 impl<'a> TryFrom<&'a Value> for &'a [u8] {
     type Error = ();
 
@@ -435,6 +628,18 @@ impl PartialEq for Value {
 mod test {
     use super::*;
 
+    #[test]
+    fn row_count_errors() {
+        assert_eq!(
+            "expected a single row, but the query returned none",
+            QueryError::UnexpectedRowCount(0).to_string()
+        );
+        assert_eq!(
+            "expected a single row, but the query returned more than one",
+            QueryError::UnexpectedRowCount(2).to_string()
+        );
+    }
+
     #[test]
     fn value_conversions() {
         let expected_text = Value::Text("a".to_string());
@@ -473,4 +678,33 @@ mod test {
         assert_eq!(Value::Null, None::<i16>.into());
         assert_eq!(expected_int, Some(123u32).into());
     }
+
+    #[test]
+    fn owned_values() {
+        assert_eq!(Ok("a".to_owned()), String::try_from(&Value::text("a")));
+        assert_eq!(Ok(b"a".to_vec()), Vec::<u8>::try_from(&Value::text("a")));
+        assert_eq!(Ok(vec![1, 2]), Vec::<u8>::try_from(&Value::blob([1, 2])));
+        assert_eq!(Err(()), String::try_from(&Value::Integer(1)));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_values() {
+        use serde_json::json;
+
+        let row = RowResult {
+            values: vec![
+                Value::Integer(1),
+                Value::Real(f64::NAN),
+                Value::text("Ada"),
+                Value::blob([0xca, 0xfe]),
+                Value::Null,
+            ],
+        };
+        let columns = ["id", "score", "name", "avatar", "email"].map(String::from);
+        assert_eq!(
+            json!({"id": 1, "score": null, "name": "Ada", "avatar": "0xcafe", "email": null}),
+            row.to_json(&columns, &JsonOptions::default())
+        );
+    }
 }
@@ -5,3 +5,41 @@ pub async fn sleep(duration: Duration) {
     let duration_ns = duration.as_nanos().try_into().unwrap_or(u64::MAX);
     crate::wasip3::clocks::monotonic_clock::wait_for(duration_ns).await;
 }
+
+/// Runs `future` until it completes or `duration` elapses, whichever comes
+/// first.
+///
+/// If the deadline passes first, `future` is dropped, cancelling any work it
+/// had not finished, and [`Elapsed`] is returned. Tasks it spawned keep
+/// running.
+///
+/// # Examples
+///
+/// ```ignore
+/// use std::time::Duration;
+///
+/// match spin_sdk::time::timeout(Duration::from_secs(2), spin_sdk::http::get("https://example.com")).await {
+///     Ok(response) => { /* ... */ }
+///     Err(_) => println!("gave up after 2 seconds"),
+/// }
+/// ```
+pub async fn timeout<F: Future>(duration: Duration, future: F) -> Result<F::Output, Elapsed> {
+    let future = std::pin::pin!(future);
+    let deadline = std::pin::pin!(sleep(duration));
+    match futures::future::select(future, deadline).await {
+        futures::future::Either::Left((output, _)) => Ok(output),
+        futures::future::Either::Right(((), _)) => Err(Elapsed),
+    }
+}
+
+/// The error returned by [`timeout`] when the deadline passes first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Elapsed;
+
+impl std::fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("deadline has elapsed")
+    }
+}
+
+impl std::error::Error for Elapsed {}
@@ -7,6 +7,16 @@
 //! the only variables that may be overridden directly (for
 //! example, on the Spin command line).
 //!
+//! # Changes to values
+//!
+//! The host does not version variables or report when they change. Each
+//! call to [`get`] asks the host for the current value, which providers such
+//! as a secrets vault may resolve afresh, while other providers fix values
+//! for the lifetime of the application. A component that caches a value,
+//! such as a feature flag, will not observe changes to it; to react to
+//! changes, call [`get`] again rather than holding the value across
+//! requests.
+//!
 //! # Examples
 //!
 //! Get the value of a component variable.
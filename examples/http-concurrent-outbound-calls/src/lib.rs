@@ -3,7 +3,7 @@ use std::time::{Duration, Instant};
 
 use futures::{SinkExt, channel::mpsc::Sender};
 use http::Request;
-use spin_sdk::http::{EmptyBody, IntoResponse, body, send};
+use spin_sdk::http::{EmptyBody, HeaderExt, IntoResponse, body, send};
 use spin_sdk::http_service;
 
 // In this streaming scenario, the entry point is a shim
@@ -83,10 +83,7 @@ async fn get_content_length(url: &str) -> anyhow::Result<TaskResult> {
     let sent_at = Instant::now();
     let response = send(request).await?;
     let time_taken = Instant::now().duration_since(sent_at);
-    let cl_header = response.headers().get("content-length");
-    let content_length = cl_header
-        .and_then(|hval| hval.to_str().ok())
-        .and_then(|hval| hval.parse().ok());
+    let content_length = response.header_parsed("content-length");
 
     Ok(TaskResult {
         url: url.to_string(),
@@ -17,7 +17,7 @@ async fn handle_streaming(request: Request) -> impl IntoResponse {
     // us to send the first bytes of the response without waiting
     // for the request to arrive in full (and without requiring us to
     // hold the whole request body in memory).
-    let mut in_body = request.into_body().stream();
+    let mut in_body = request.into_body().into_byte_stream();
 
     // Create a streaming Body implementation that backs onto a `mpsc`
     // channel. The function returns the sender side of the channel; the